@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+use chessmate::{Board, START_FEN};
+
+//Standard perft positions, at depths that keep the whole run to a few seconds
+const POSITIONS: [(&str, usize); 6] = [
+    (START_FEN, 5),
+    (
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        4,
+    ),
+    ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 6),
+    (
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        4,
+    ),
+    (
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        4,
+    ),
+    (
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        4,
+    ),
+];
+
+/// Unhashed perft over the standard positions, reporting move generation
+/// speed. `depth` replaces the default depth of every position.
+pub fn run(depth: Option<usize>) {
+    let mut total_nodes = 0;
+    let mut total_time = Duration::ZERO;
+    for (fen, default_depth) in POSITIONS {
+        let depth = depth.unwrap_or(default_depth);
+        let mut board = Board::from_fen(fen).unwrap();
+        let start = Instant::now();
+        let nodes = board.perft(depth);
+        let time = start.elapsed();
+        println!(
+            "{:>10} nodes {:>6} ms  depth {}  {}",
+            nodes,
+            time.as_millis(),
+            depth,
+            fen
+        );
+        total_nodes += nodes;
+        total_time += time;
+    }
+    println!(
+        "Total: {} nodes in {} ms, {} nps",
+        total_nodes,
+        total_time.as_millis(),
+        (total_nodes as f64 / total_time.as_secs_f64()) as u64
+    );
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use super::{
+    BitBoard, Board, Piece, Square, BLACK_KINGSIDE, BLACK_QUEENSIDE, WHITE_KINGSIDE,
+    WHITE_QUEENSIDE,
+};
+
+const BACK_RANKS: BitBoard = 0xFF000000000000FF;
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// The FEN field that could not be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FenError {
+    /// Fewer than the four mandatory fields were given.
+    MissingFields,
+    /// More than six fields were given.
+    TooManyFields,
+    PiecePlacement,
+    ActiveColor,
+    Castling,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+    /// The side that just moved has left its king in check.
+    OpponentInCheck,
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = match self {
+            FenError::MissingFields => return write!(f, "FEN is missing mandatory fields"),
+            FenError::TooManyFields => return write!(f, "FEN has too many fields"),
+            FenError::OpponentInCheck => return write!(f, "side not to move is in check in FEN"),
+            FenError::PiecePlacement => "piece placement",
+            FenError::ActiveColor => "active color",
+            FenError::Castling => "castling availability",
+            FenError::EnPassant => "en passant target square",
+            FenError::HalfmoveClock => "halfmove clock",
+            FenError::FullmoveNumber => "fullmove number",
+        };
+        write!(f, "invalid {} field in FEN", field)
+    }
+}
+
+impl std::error::Error for FenError {}
+
+impl Board {
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 {
+            return Err(FenError::MissingFields);
+        }
+        if fields.len() > 6 {
+            return Err(FenError::TooManyFields);
+        }
+        let mut board = Board::empty();
+
+        //Ranks are listed from the eighth down to the first
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::PiecePlacement);
+        }
+        for (i, rank) in ranks.iter().enumerate() {
+            let rank_start = (7 - i) * 8;
+            let mut file = 0;
+            for c in rank.chars() {
+                if let Some(skip) = c.to_digit(10) {
+                    if skip == 0 || skip > 8 {
+                        return Err(FenError::PiecePlacement);
+                    }
+                    file += skip as usize;
+                    continue;
+                }
+                if file > 7 {
+                    return Err(FenError::PiecePlacement);
+                }
+                let square: BitBoard = 1 << (rank_start + file);
+                match c {
+                    'P' => board.white_pawns |= square,
+                    'N' => board.white_knights |= square,
+                    'B' => board.white_bishops |= square,
+                    'R' => board.white_rooks |= square,
+                    'Q' => board.white_queens |= square,
+                    'K' => board.white_kings |= square,
+                    'p' => board.black_pawns |= square,
+                    'n' => board.black_knights |= square,
+                    'b' => board.black_bishops |= square,
+                    'r' => board.black_rooks |= square,
+                    'q' => board.black_queens |= square,
+                    'k' => board.black_kings |= square,
+                    _ => return Err(FenError::PiecePlacement),
+                }
+                file += 1;
+            }
+            if file != 8 {
+                return Err(FenError::PiecePlacement);
+            }
+        }
+        //Move generation assumes exactly one king per side
+        if board.white_kings.count_ones() != 1 || board.black_kings.count_ones() != 1 {
+            return Err(FenError::PiecePlacement);
+        }
+        //Pawns can't stand on the back ranks, they promote on reaching them
+        if (board.white_pawns | board.black_pawns) & BACK_RANKS != 0 {
+            return Err(FenError::PiecePlacement);
+        }
+        board.white_occupied = board.white_pawns
+            | board.white_knights
+            | board.white_bishops
+            | board.white_rooks
+            | board.white_queens
+            | board.white_kings;
+        board.black_occupied = board.black_pawns
+            | board.black_knights
+            | board.black_bishops
+            | board.black_rooks
+            | board.black_queens
+            | board.black_kings;
+        board.occupied = board.white_occupied | board.black_occupied;
+
+        board.white_to_play = match fields[1] {
+            "w" => true,
+            "b" => false,
+            _ => return Err(FenError::ActiveColor),
+        };
+        //Otherwise the king could be captured, which move generation can't handle
+        let opponent_king = board.pieces(!board.white_to_play, Piece::King);
+        if board.under_attack_by(board.white_to_play) & opponent_king != 0 {
+            return Err(FenError::OpponentInCheck);
+        }
+
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                let (right, king, rook) = match c {
+                    'K' => (WHITE_KINGSIDE, board.white_kings, board.white_rooks),
+                    'Q' => (WHITE_QUEENSIDE, board.white_kings, board.white_rooks),
+                    'k' => (BLACK_KINGSIDE, board.black_kings, board.black_rooks),
+                    'q' => (BLACK_QUEENSIDE, board.black_kings, board.black_rooks),
+                    _ => return Err(FenError::Castling),
+                };
+                //Each right may appear once and needs its king and rook on their home squares
+                if board.castle & right == right || right & !(king | rook) != 0 {
+                    return Err(FenError::Castling);
+                }
+                board.castle |= right;
+            }
+        }
+
+        if fields[3] != "-" {
+            let square: Square = fields[3].parse().map_err(|_| FenError::EnPassant)?;
+            let expected_rank = if board.white_to_play { 5 } else { 2 };
+            if square.rank() != expected_rank {
+                return Err(FenError::EnPassant);
+            }
+            //The pawn that just moved two squares has to be past the target,
+            //with the squares it crossed left empty
+            let target: BitBoard = 1 << square.index();
+            let (pushed, origin) = if board.white_to_play {
+                (target >> 8, target << 8)
+            } else {
+                (target << 8, target >> 8)
+            };
+            if board.pieces(!board.white_to_play, Piece::Pawn) & pushed == 0
+                || board.occupied & (target | origin) != 0
+            {
+                return Err(FenError::EnPassant);
+            }
+            board.en_passant = target;
+        }
+
+        if let Some(halfmove) = fields.get(4) {
+            board.halfmove_clock = halfmove.parse().map_err(|_| FenError::HalfmoveClock)?;
+        }
+        if let Some(fullmove) = fields.get(5) {
+            board.fullmove_number = match fullmove.parse() {
+                Ok(0) | Err(_) => return Err(FenError::FullmoveNumber),
+                Ok(n) => n,
+            };
+        }
+        board.hash = board.compute_hash();
+        board.pawn_hash = board.compute_pawn_hash();
+        Ok(board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut res = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                let square = rank * 8 + file;
+                match self.piece_char(square) {
+                    Some(c) => {
+                        if empty != 0 {
+                            res.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        res.push(c);
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty != 0 {
+                res.push_str(&empty.to_string());
+            }
+            if rank != 0 {
+                res.push('/');
+            }
+        }
+
+        res.push(' ');
+        res.push(if self.white_to_play { 'w' } else { 'b' });
+
+        res.push(' ');
+        let castle_len = res.len();
+        if self.castle & WHITE_KINGSIDE == WHITE_KINGSIDE {
+            res.push('K');
+        }
+        if self.castle & WHITE_QUEENSIDE == WHITE_QUEENSIDE {
+            res.push('Q');
+        }
+        if self.castle & BLACK_KINGSIDE == BLACK_KINGSIDE {
+            res.push('k');
+        }
+        if self.castle & BLACK_QUEENSIDE == BLACK_QUEENSIDE {
+            res.push('q');
+        }
+        if res.len() == castle_len {
+            res.push('-');
+        }
+
+        res.push(' ');
+        if self.en_passant == 0 {
+            res.push('-');
+        } else {
+            let square = Square::from_index_unchecked(self.en_passant.trailing_zeros() as usize);
+            res.push_str(&square.to_string());
+        }
+
+        res.push_str(&format!(
+            " {} {}",
+            self.halfmove_clock, self.fullmove_number
+        ));
+        res
+    }
+
+    fn piece_char(&self, square: usize) -> Option<char> {
+        let mask: BitBoard = 1 << square;
+        let pieces = [
+            (self.white_pawns, 'P'),
+            (self.white_knights, 'N'),
+            (self.white_bishops, 'B'),
+            (self.white_rooks, 'R'),
+            (self.white_queens, 'Q'),
+            (self.white_kings, 'K'),
+            (self.black_pawns, 'p'),
+            (self.black_knights, 'n'),
+            (self.black_bishops, 'b'),
+            (self.black_rooks, 'r'),
+            (self.black_queens, 'q'),
+            (self.black_kings, 'k'),
+        ];
+        pieces
+            .iter()
+            .find(|(board, _)| board & mask != 0)
+            .map(|(_, c)| *c)
+    }
+}
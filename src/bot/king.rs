@@ -0,0 +1,144 @@
+use super::eval::{s, Score};
+use super::pawns::{forward_one, relative_rank};
+use super::pseudomoves::*;
+use super::utils::*;
+use super::{BitBoard, Board, Piece};
+
+const FILE_A: BitBoard = 0x0101010101010101;
+
+//Danger units per king zone square hit, for knight, bishop, rook and queen
+const ATTACK_WEIGHTS: [(Piece, i32); 4] = [
+    (Piece::Knight, 2),
+    (Piece::Bishop, 2),
+    (Piece::Rook, 3),
+    (Piece::Queen, 5),
+];
+//A lone attacker rarely gets anywhere without help
+const MIN_ATTACKERS: i32 = 2;
+
+//Indexed by danger units, grows quadratically until it levels off
+const KING_DANGER: [Score; 64] = {
+    let mut res = [s(0, 0); 64];
+    let mut units = 0;
+    while units < 64 {
+        let mg = units * units / 4;
+        res[units as usize] = s(if mg < 500 { mg } else { 500 }, units);
+        units += 1;
+    }
+    res
+};
+
+//Indexed by how many ranks in front of the king the nearest pawn on the file
+//is, 0 for none
+const SHIELD: [Score; 8] = [
+    s(0, 0),
+    s(24, 0),
+    s(14, 0),
+    s(4, 0),
+    s(0, 0),
+    s(0, 0),
+    s(0, 0),
+    s(0, 0),
+];
+const STORM: [Score; 8] = [
+    s(0, 0),
+    s(-4, 0),
+    s(-30, -5),
+    s(-18, 0),
+    s(-8, 0),
+    s(0, 0),
+    s(0, 0),
+    s(0, 0),
+];
+const SEMI_OPEN_FILE: Score = s(-12, 0);
+const OPEN_FILE: Score = s(-20, 0);
+
+/// How safe one side's king is, each term already signed for that side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KingTerms {
+    pub attacks: Score,
+    pub shield: Score,
+    pub storm: Score,
+    pub open_files: Score,
+}
+
+impl KingTerms {
+    pub fn total(&self) -> Score {
+        self.attacks + self.shield + self.storm + self.open_files
+    }
+}
+
+//Squares a knight, bishop, rook or queen on the square attacks
+fn piece_attacks(piece: Piece, square: usize, empty: BitBoard) -> BitBoard {
+    match piece {
+        Piece::Knight => KNIGHT_MOVES[square],
+        Piece::Bishop => bishop_moves(square, empty),
+        Piece::Rook => rook_moves(square, empty),
+        _ => queen_moves(square, empty),
+    }
+}
+
+impl Board {
+    pub(super) fn king_safety(&self, white: bool) -> KingTerms {
+        let mut terms = KingTerms::default();
+        let king = self.pieces(white, Piece::King);
+        if king == 0 {
+            return terms;
+        }
+        let king_square = king.trailing_zeros() as usize;
+        //The squares around the king plus one more rank towards the enemy
+        let zone = king | KING_MOVES[king_square] | forward_one(white, KING_MOVES[king_square]);
+
+        let empty = !self.occupied;
+        let mut attackers = 0;
+        let mut units = 0;
+        for (piece, weight) in ATTACK_WEIGHTS {
+            for square in BitBoardIter(self.pieces(!white, piece)) {
+                let hits = piece_attacks(piece, square, empty) & zone;
+                if hits != 0 {
+                    attackers += 1;
+                    units += weight * hits.count_ones() as i32;
+                }
+            }
+        }
+        if attackers < MIN_ATTACKERS {
+            units = 0;
+        }
+        units += (zone & self.under_attack_by(!white)).count_ones() as i32;
+        terms.attacks = -KING_DANGER[units.min(63) as usize];
+
+        let ours = self.pieces(white, Piece::Pawn);
+        let theirs = self.pieces(!white, Piece::Pawn);
+        let king_rank = relative_rank(white, king_square);
+        //Always three files, so a king in the corner still looks at b and c
+        let centre = (king_square % 8).clamp(1, 6);
+        for file in centre - 1..=centre + 1 {
+            let file_mask = FILE_A << file;
+            if ours & file_mask == 0 {
+                terms.open_files += if theirs & file_mask == 0 {
+                    OPEN_FILE
+                } else {
+                    SEMI_OPEN_FILE
+                };
+            }
+            let shield = nearest_ahead(white, king_rank, ours & file_mask);
+            terms.shield += SHIELD[shield];
+            //A storming pawn held up by one of ours can't open the file
+            let storm = nearest_ahead(white, king_rank, theirs & file_mask);
+            if storm != 0 && (storm == 1 || shield != storm - 1) {
+                terms.storm += STORM[storm];
+            }
+        }
+        terms
+    }
+}
+
+//Ranks between the king and the closest of the pawns in front of it
+fn nearest_ahead(white: bool, king_rank: usize, pawns: BitBoard) -> usize {
+    BitBoardIter(pawns)
+        .map(|square| relative_rank(white, square))
+        .filter(|&rank| rank > king_rank)
+        .map(|rank| rank - king_rank)
+        .min()
+        .unwrap_or(0)
+}
@@ -0,0 +1,269 @@
+use super::pseudomoves::{ray_bishop_moves, ray_rook_moves};
+use super::BitBoard;
+
+#[cfg(all(
+    feature = "pext",
+    not(all(target_arch = "x86_64", target_feature = "bmi2"))
+))]
+compile_error!("the pext feature needs BMI2, build with RUSTFLAGS=\"-C target-feature=+bmi2\"");
+
+const FILE_A: BitBoard = 0x0101010101010101;
+const FILE_H: BitBoard = 0x8080808080808080;
+const RANK_1: BitBoard = 0x00000000000000FF;
+const RANK_8: BitBoard = 0xFF00000000000000;
+
+//Fancy magics with the fewest possible index bits, found by trial and error
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080004008801020,
+    0x0840092002c03000,
+    0x1900200010400900,
+    0x0880100008000480,
+    0x4200100420080200,
+    0x8100020100080400,
+    0x0200040110886200,
+    0x0200008040220411,
+    0x0404800084400220,
+    0x0000401000402000,
+    0x0086001081220440,
+    0x0408800800100280,
+    0x000a001201040820,
+    0x8848800200840080,
+    0x4001000100040200,
+    0x0442000102105084,
+    0x9080010020804100,
+    0x0040404000201009,
+    0x0000808010002009,
+    0x2200090021d00100,
+    0x0008008008040080,
+    0x0004004002010040,
+    0x0011040008015042,
+    0x00000a0001768104,
+    0x0000800080204009,
+    0x2010004140002001,
+    0x9800200280100080,
+    0x1000100080080080,
+    0x0442000a00049020,
+    0x2100040080020080,
+    0x0800120400900148,
+    0x0010040a00128541,
+    0x2800804000800030,
+    0x1010002000400041,
+    0x4000200011004100,
+    0x0610008410800800,
+    0x0400802402800800,
+    0xc100020080800400,
+    0x0002000802000401,
+    0x0182085882000401,
+    0x0220204000808000,
+    0x2860100040024022,
+    0x0001002004110040,
+    0x99101042000a0020,
+    0x0004080004008080,
+    0x0010040002008080,
+    0x2012004881020004,
+    0x8300842444820011,
+    0x0088403882010200,
+    0x0820400080210100,
+    0x0110910040a00300,
+    0x0801100280080480,
+    0x0242009008200600,
+    0x1002000489500200,
+    0x0040800200010080,
+    0x0091800041000080,
+    0x0000209300488001,
+    0x04c1002414824001,
+    0x020020000b001041,
+    0x7000100004200901,
+    0x8002002004100802,
+    0x30010002084c0007,
+    0x0888221800813004,
+    0x4000002840840112,
+];
+const BISHOP_MAGICS: [u64; 64] = [
+    0xa010041108003100,
+    0x006082020a002900,
+    0x6810010619200000,
+    0x08281a0520000408,
+    0x0001104001000400,
+    0x0018901008048400,
+    0x00040a0210245280,
+    0x000200210808a402,
+    0x9140048410821200,
+    0x0800091010820041,
+    0x20504804832202c0,
+    0x0100091401081000,
+    0x8021011140000012,
+    0x0810020804450400,
+    0x208b0542109008a2,
+    0x0080084a08040204,
+    0x0040e2a80811244c,
+    0x2505022008008108,
+    0x0430220100420040,
+    0x010a040420220040,
+    0x1105000290400000,
+    0x0093001200822120,
+    0x4000a62048043004,
+    0x280120048a015004,
+    0x006090002a020814,
+    0x44042000240800d0,
+    0x01102800040a4400,
+    0x1004080080220040,
+    0x0001001011004024,
+    0x0010044000805040,
+    0x0914041200820100,
+    0x0004821012821480,
+    0x0024040500c05021,
+    0x0088611002080200,
+    0x0116080a00040020,
+    0x4000020080080080,
+    0x2450450140840040,
+    0x0000880201484100,
+    0x0222020404020092,
+    0x8081110600002e00,
+    0x2842101105000801,
+    0x1100809008001025,
+    0x00020202221c0400,
+    0x0422014022009020,
+    0x0210046102100c00,
+    0xc004008082029102,
+    0x00aa461801101200,
+    0x0404080080201108,
+    0x020542108c205002,
+    0x0410544804100100,
+    0x0040910841100000,
+    0x0400200042021100,
+    0x00004204850400c0,
+    0x0200100410a42102,
+    0x1040020801210102,
+    0x0805040410420000,
+    0x2884804130100200,
+    0x800c262201242000,
+    0x1058000194108800,
+    0x0014221054420204,
+    0x0104000012a02200,
+    0x0200881003300100,
+    0x0140400202840100,
+    0x0402020801010201,
+];
+
+//Sum over all squares of two to the number of relevant blockers
+const ROOK_TABLE_SIZE: usize = 102400;
+const BISHOP_TABLE_SIZE: usize = 5248;
+
+#[derive(Clone, Copy)]
+struct Magic {
+    //Squares whose occupancy changes the attacks, the edges never do
+    mask: BitBoard,
+    //PEXT only needs the mask
+    #[cfg_attr(feature = "pext", allow(dead_code))]
+    magic: u64,
+    #[cfg_attr(feature = "pext", allow(dead_code))]
+    shift: u32,
+    offset: usize,
+}
+
+struct Table<const N: usize> {
+    magics: [Magic; 64],
+    attacks: [BitBoard; N],
+}
+
+static ROOK_TABLE: Table<ROOK_TABLE_SIZE> = build(true, &ROOK_MAGICS);
+static BISHOP_TABLE: Table<BISHOP_TABLE_SIZE> = build(false, &BISHOP_MAGICS);
+
+const fn slider_moves(rook: bool, square: usize, occupied: BitBoard) -> BitBoard {
+    if rook {
+        ray_rook_moves(square, !occupied)
+    } else {
+        ray_bishop_moves(square, !occupied)
+    }
+}
+
+const fn relevant_mask(rook: bool, square: usize) -> BitBoard {
+    let rays = super::pseudomoves::RAYS;
+    if rook {
+        //The last square of each ray is never blocking anything
+        (rays[0][square] & !RANK_8)
+            | (rays[2][square] & !FILE_H)
+            | (rays[4][square] & !RANK_1)
+            | (rays[6][square] & !FILE_A)
+    } else {
+        let edges = FILE_A | FILE_H | RANK_1 | RANK_8;
+        (rays[1][square] | rays[3][square] | rays[5][square] | rays[7][square]) & !edges
+    }
+}
+
+//Index of `occupied` in the square's slice of the table. `count` is the
+//position of the subset in carry-rippler order, which is what PEXT gives.
+#[allow(unused_variables)]
+const fn table_index(magic: &Magic, occupied: BitBoard, count: usize) -> usize {
+    #[cfg(feature = "pext")]
+    {
+        magic.offset + count
+    }
+    #[cfg(not(feature = "pext"))]
+    {
+        magic.offset + ((occupied & magic.mask).wrapping_mul(magic.magic) >> magic.shift) as usize
+    }
+}
+
+const fn build<const N: usize>(rook: bool, magic_numbers: &[u64; 64]) -> Table<N> {
+    let mut table = Table {
+        magics: [Magic {
+            mask: 0,
+            magic: 0,
+            shift: 0,
+            offset: 0,
+        }; 64],
+        attacks: [0; N],
+    };
+    let mut offset = 0;
+    let mut square = 0;
+    while square < 64 {
+        let mask = relevant_mask(rook, square);
+        let magic = Magic {
+            mask,
+            magic: magic_numbers[square],
+            shift: 64 - mask.count_ones(),
+            offset,
+        };
+        //Carry-rippler walk over every subset of the mask
+        let mut subset: BitBoard = 0;
+        let mut count = 0;
+        loop {
+            let index = table_index(&magic, subset, count);
+            table.attacks[index] = slider_moves(rook, square, subset);
+            count += 1;
+            subset = subset.wrapping_sub(mask) & mask;
+            if subset == 0 {
+                break;
+            }
+        }
+        table.magics[square] = magic;
+        offset += count;
+        square += 1;
+    }
+    assert!(offset == N);
+    table
+}
+
+#[inline]
+fn lookup<const N: usize>(table: &Table<N>, square: usize, occupied: BitBoard) -> BitBoard {
+    let magic = &table.magics[square];
+    //BMI2 is checked for at compile time above
+    #[cfg(feature = "pext")]
+    let index =
+        magic.offset + unsafe { std::arch::x86_64::_pext_u64(occupied, magic.mask) } as usize;
+    #[cfg(not(feature = "pext"))]
+    let index = table_index(magic, occupied, 0);
+    table.attacks[index]
+}
+
+#[inline]
+pub fn rook_attacks(square: usize, occupied: BitBoard) -> BitBoard {
+    lookup(&ROOK_TABLE, square, occupied)
+}
+
+#[inline]
+pub fn bishop_attacks(square: usize, occupied: BitBoard) -> BitBoard {
+    lookup(&BISHOP_TABLE, square, occupied)
+}
@@ -1,34 +1,37 @@
+use super::movegen::backward;
 use super::pseudomoves::*;
 use super::utils::*;
-use super::{BitBoard, Board};
+use super::{BitBoard, Board, Piece};
 
+//The masks are all for the king of the side given by `WHITE`
 impl Board {
     #[inline]
-    pub fn black_checkmask(&self) -> BitBoard {
-        let king_square = self.black_kings.trailing_zeros() as usize;
+    pub(super) fn checkmask<const WHITE: bool>(&self) -> BitBoard {
+        let king = self.pieces(WHITE, Piece::King);
+        let king_square = king.trailing_zeros() as usize;
         let mut checkmask: BitBoard = 0xFFFFFFFFFFFFFFFF;
-        for i in BitBoardIter(self.white_queens) {
-            if queen_moves(i, !self.occupied) & self.black_kings != 0 {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Queen)) {
+            if queen_moves(i, !self.occupied) & king != 0 {
                 checkmask &= PATH_BETWEEN[king_square][i];
             }
         }
-        for i in BitBoardIter(self.white_rooks) {
-            if rook_moves(i, !self.occupied) & self.black_kings != 0 {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Rook)) {
+            if rook_moves(i, !self.occupied) & king != 0 {
                 checkmask &= PATH_BETWEEN[king_square][i];
             }
         }
-        for i in BitBoardIter(self.white_bishops) {
-            if bishop_moves(i, !self.occupied) & self.black_kings != 0 {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Bishop)) {
+            if bishop_moves(i, !self.occupied) & king != 0 {
                 checkmask &= PATH_BETWEEN[king_square][i];
             }
         }
-        for i in BitBoardIter(self.white_knights) {
-            if KNIGHT_MOVES[i] & self.black_kings != 0 {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Knight)) {
+            if KNIGHT_MOVES[i] & king != 0 {
                 checkmask &= (1 as BitBoard) << i;
             }
         }
-        for i in BitBoardIter(self.white_pawns) {
-            if PAWN_ATTACKS[0][i] & self.black_kings != 0 {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Pawn)) {
+            if PAWN_ATTACKS[WHITE as usize][i] & king != 0 {
                 checkmask &= (1 as BitBoard) << i;
             }
         }
@@ -36,18 +39,19 @@ impl Board {
     }
 
     #[inline]
-    pub fn black_pinmask_hv(&self) -> BitBoard {
-        let king_square = self.black_kings.trailing_zeros() as usize;
+    pub(super) fn pinmask_hv<const WHITE: bool>(&self) -> BitBoard {
+        let king = self.pieces(WHITE, Piece::King);
+        let king_square = king.trailing_zeros() as usize;
         let mut pinmask: BitBoard = 0;
-        for i in BitBoardIter(self.white_queens) {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Queen)) {
             let xray = rook_xray(i, !self.occupied);
-            if xray & self.black_kings != 0 {
+            if xray & king != 0 {
                 pinmask |= PATH_BETWEEN[king_square][i];
             }
         }
-        for i in BitBoardIter(self.white_rooks) {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Rook)) {
             let xray = rook_xray(i, !self.occupied);
-            if xray & self.black_kings != 0 {
+            if xray & king != 0 {
                 pinmask |= PATH_BETWEEN[king_square][i];
             }
         }
@@ -55,139 +59,60 @@ impl Board {
     }
 
     #[inline]
-    pub fn black_pinmask_d(&self) -> BitBoard {
-        let king_square = self.black_kings.trailing_zeros() as usize;
+    pub(super) fn pinmask_d<const WHITE: bool>(&self) -> BitBoard {
+        let king = self.pieces(WHITE, Piece::King);
+        let king_square = king.trailing_zeros() as usize;
         let mut pinmask: BitBoard = 0;
-        for i in BitBoardIter(self.white_queens) {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Queen)) {
             let xray = bishop_xray(i, !self.occupied);
-            if xray & self.black_kings != 0 {
+            if xray & king != 0 {
                 pinmask |= PATH_BETWEEN[king_square][i];
             }
         }
-        for i in BitBoardIter(self.white_bishops) {
+        for i in BitBoardIter(self.pieces(!WHITE, Piece::Bishop)) {
             let xray = bishop_xray(i, !self.occupied);
-            if xray & self.black_kings != 0 {
+            if xray & king != 0 {
                 pinmask |= PATH_BETWEEN[king_square][i];
             }
         }
         pinmask
     }
 
+    //Squares attacked by the given side. The other king doesn't block, so it
+    //can't step back along the line it is attacked on.
     #[inline]
-    pub fn under_attack_by_black(&self) -> BitBoard {
+    pub(super) fn under_attack_by(&self, white: bool) -> BitBoard {
+        let empty = !(self.occupied & !self.pieces(!white, Piece::King));
         let mut res = 0;
-        for i in BitBoardIter(self.black_queens) {
-            res |= queen_moves(i, !(self.occupied & !self.white_kings));
+        for i in BitBoardIter(self.pieces(white, Piece::Queen)) {
+            res |= queen_moves(i, empty);
         }
-        for i in BitBoardIter(self.black_rooks) {
-            res |= rook_moves(i, !(self.occupied & !self.white_kings));
+        for i in BitBoardIter(self.pieces(white, Piece::Rook)) {
+            res |= rook_moves(i, empty);
         }
-        for i in BitBoardIter(self.black_bishops) {
-            res |= bishop_moves(i, !(self.occupied & !self.white_kings));
+        for i in BitBoardIter(self.pieces(white, Piece::Bishop)) {
+            res |= bishop_moves(i, empty);
         }
-        for i in BitBoardIter(self.black_knights) {
+        for i in BitBoardIter(self.pieces(white, Piece::Knight)) {
             res |= KNIGHT_MOVES[i];
         }
-        for i in BitBoardIter(self.black_kings) {
+        for i in BitBoardIter(self.pieces(white, Piece::King)) {
             res |= KING_MOVES[i];
         }
-        for i in BitBoardIter(self.black_pawns) {
-            res |= PAWN_ATTACKS[1][i];
+        for i in BitBoardIter(self.pieces(white, Piece::Pawn)) {
+            res |= PAWN_ATTACKS[!white as usize][i];
         }
         res
     }
 
+    //Taking en passant removes two pawns from the same rank at once, which can
+    //expose the king along that rank even though neither pawn is pinned
     #[inline]
-    pub fn white_checkmask(&self) -> BitBoard {
-        let king_square = self.white_kings.trailing_zeros() as usize;
-        let mut checkmask: BitBoard = 0xFFFFFFFFFFFFFFFF;
-        for i in BitBoardIter(self.black_queens) {
-            if queen_moves(i, !self.occupied) & self.white_kings != 0 {
-                checkmask &= PATH_BETWEEN[king_square][i];
-            }
-        }
-        for i in BitBoardIter(self.black_rooks) {
-            if rook_moves(i, !self.occupied) & self.white_kings != 0 {
-                checkmask &= PATH_BETWEEN[king_square][i];
-            }
-        }
-        for i in BitBoardIter(self.black_bishops) {
-            if bishop_moves(i, !self.occupied) & self.white_kings != 0 {
-                checkmask &= PATH_BETWEEN[king_square][i];
-            }
-        }
-        for i in BitBoardIter(self.black_knights) {
-            if KNIGHT_MOVES[i] & self.white_kings != 0 {
-                checkmask &= (1 as BitBoard) << i;
-            }
-        }
-        for i in BitBoardIter(self.black_pawns) {
-            if PAWN_ATTACKS[1][i] & self.white_kings != 0 {
-                checkmask &= (1 as BitBoard) << i;
-            }
-        }
-        checkmask
-    }
-
-    #[inline]
-    pub fn white_pinmask_hv(&self) -> BitBoard {
-        let king_square = self.white_kings.trailing_zeros() as usize;
-        let mut pinmask: BitBoard = 0;
-        for i in BitBoardIter(self.black_queens) {
-            let xray = rook_xray(i, !self.occupied);
-            if xray & self.white_kings != 0 {
-                pinmask |= PATH_BETWEEN[king_square][i];
-            }
-        }
-        for i in BitBoardIter(self.black_rooks) {
-            let xray = rook_xray(i, !self.occupied);
-            if xray & self.white_kings != 0 {
-                pinmask |= PATH_BETWEEN[king_square][i];
-            }
-        }
-        pinmask
-    }
-
-    #[inline]
-    pub fn white_pinmask_d(&self) -> BitBoard {
-        let king_square = self.white_kings.trailing_zeros() as usize;
-        let mut pinmask: BitBoard = 0;
-        for i in BitBoardIter(self.black_queens) {
-            let xray = bishop_xray(i, !self.occupied);
-            if xray & self.white_kings != 0 {
-                pinmask |= PATH_BETWEEN[king_square][i];
-            }
-        }
-        for i in BitBoardIter(self.black_bishops) {
-            let xray = bishop_xray(i, !self.occupied);
-            if xray & self.white_kings != 0 {
-                pinmask |= PATH_BETWEEN[king_square][i];
-            }
-        }
-        pinmask
-    }
-
-    #[inline]
-    pub fn under_attack_by_white(&self) -> BitBoard {
-        let mut res = 0;
-        for i in BitBoardIter(self.white_queens) {
-            res |= queen_moves(i, !(self.occupied & !self.black_kings));
-        }
-        for i in BitBoardIter(self.white_rooks) {
-            res |= rook_moves(i, !(self.occupied & !self.black_kings));
-        }
-        for i in BitBoardIter(self.white_bishops) {
-            res |= bishop_moves(i, !(self.occupied & !self.black_kings));
-        }
-        for i in BitBoardIter(self.white_knights) {
-            res |= KNIGHT_MOVES[i];
-        }
-        for i in BitBoardIter(self.white_kings) {
-            res |= KING_MOVES[i];
-        }
-        for i in BitBoardIter(self.white_pawns) {
-            res |= PAWN_ATTACKS[0][i];
-        }
-        res
+    pub(super) fn en_passant_exposes_king<const WHITE: bool>(&self, from: usize) -> bool {
+        let king_square = self.pieces(WHITE, Piece::King).trailing_zeros() as usize;
+        let occupied =
+            (self.occupied ^ (1 << from) ^ backward::<WHITE>(self.en_passant)) | self.en_passant;
+        let attackers = self.pieces(!WHITE, Piece::Rook) | self.pieces(!WHITE, Piece::Queen);
+        rook_moves(king_square, !occupied) & attackers != 0
     }
 }
@@ -0,0 +1,58 @@
+use super::eval::{s, Score};
+use super::pawns::pawn_attacks;
+use super::pseudomoves::*;
+use super::utils::*;
+use super::{Board, Piece};
+
+//Indexed by the number of safe squares the piece can move to. Values are
+//Stockfish's classical ones halved to about centipawns.
+#[rustfmt::skip]
+const KNIGHT: [Score; 9] = [
+    s(-31, -40), s(-26, -28), s(-6, -15), s(-2, -8), s(2, 3), s(6, 6), s(11, 8), s(14, 10),
+    s(16, 12),
+];
+#[rustfmt::skip]
+const BISHOP: [Score; 14] = [
+    s(-24, -30), s(-10, -12), s(8, -2), s(13, 6), s(19, 12), s(25, 21), s(27, 27), s(31, 28),
+    s(31, 32), s(34, 36), s(40, 39), s(40, 43), s(45, 44), s(49, 48),
+];
+#[rustfmt::skip]
+const ROOK: [Score; 15] = [
+    s(-30, -39), s(-10, -8), s(1, 11), s(1, 19), s(1, 35), s(5, 49), s(11, 51), s(15, 60),
+    s(20, 67), s(20, 69), s(20, 79), s(24, 82), s(28, 84), s(28, 84), s(31, 86),
+];
+#[rustfmt::skip]
+const QUEEN: [Score; 28] = [
+    s(-15, -24), s(-6, -15), s(-4, -3), s(-4, 9), s(10, 20), s(11, 27), s(11, 29), s(17, 37),
+    s(19, 39), s(26, 48), s(32, 48), s(32, 50), s(32, 60), s(33, 63), s(33, 65), s(33, 66),
+    s(36, 68), s(36, 70), s(38, 73), s(39, 75), s(46, 75), s(54, 84), s(54, 84), s(54, 85),
+    s(55, 91), s(57, 91), s(57, 96), s(58, 109),
+];
+
+impl Board {
+    /// Mobility bonus for one side, counting only squares that aren't taken
+    /// by our own pieces or attacked by enemy pawns.
+    pub(super) fn mobility(&self, white: bool) -> Score {
+        let own = if white {
+            self.white_occupied
+        } else {
+            self.black_occupied
+        };
+        let area = !own & !pawn_attacks(!white, self.pieces(!white, Piece::Pawn));
+        let empty = !self.occupied;
+        let mut res = Score::default();
+        for square in BitBoardIter(self.pieces(white, Piece::Knight)) {
+            res += KNIGHT[(KNIGHT_MOVES[square] & area).count_ones() as usize];
+        }
+        for square in BitBoardIter(self.pieces(white, Piece::Bishop)) {
+            res += BISHOP[(bishop_moves(square, empty) & area).count_ones() as usize];
+        }
+        for square in BitBoardIter(self.pieces(white, Piece::Rook)) {
+            res += ROOK[(rook_moves(square, empty) & area).count_ones() as usize];
+        }
+        for square in BitBoardIter(self.pieces(white, Piece::Queen)) {
+            res += QUEEN[(queen_moves(square, empty) & area).count_ones() as usize];
+        }
+        res
+    }
+}
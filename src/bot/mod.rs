@@ -1,30 +1,78 @@
-use self::utils::*;
+pub use self::eval::Score;
+pub use self::fen::{FenError, START_FEN};
+pub use self::moves::{Move, MoveList, UciMoveError};
+pub use self::outcome::Outcome;
+pub use self::pgn::{Game, GameMove, PgnError, PgnErrorKind, PgnReader};
+pub use self::san::SanError;
+pub use self::search::{SearchLimits, SearchResult};
+pub use self::square::{ParseSquareError, Square};
+pub use self::trace::{EvalTrace, TraceTerm};
+pub use self::transposition::TranspositionTable;
 
-mod black_moves;
+mod eval;
+mod fen;
+mod king;
+mod magic;
 mod masks;
+mod mobility;
+mod movegen;
+mod moves;
+mod outcome;
+mod pawns;
+mod pgn;
 mod pseudomoves;
+mod san;
+mod search;
+mod square;
+#[cfg(test)]
+mod tests;
+mod trace;
+mod transposition;
 mod utils;
-mod white_moves;
+mod zobrist;
 
 type BitBoard = u64;
 
-#[derive(Debug)]
-pub struct BoardMove {
-    from: usize,
-    to: usize,
-    piece: Piece,
-    taken: Piece,
-    promotion: Piece,
-    white: bool,
-    en_passant: bool,
-    last_castle: BitBoard,
-    last_en_passant: BitBoard,
-    last_white_occupied: BitBoard,
-    last_black_occupied: BitBoard,
-    last_occupied: BitBoard,
+//King and rook squares that have to be set in `castle` for each right
+const WHITE_KINGSIDE: BitBoard = 0x0000000000000090;
+const WHITE_QUEENSIDE: BitBoard = 0x0000000000000011;
+const BLACK_KINGSIDE: BitBoard = 0x9000000000000000;
+const BLACK_QUEENSIDE: BitBoard = 0x1100000000000000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Black,
 }
 
-#[derive(Clone, Debug)]
+impl Color {
+    #[inline]
+    pub fn is_white(self) -> bool {
+        self == Color::White
+    }
+
+    #[inline]
+    fn from_white(white: bool) -> Color {
+        if white {
+            Color::White
+        } else {
+            Color::Black
+        }
+    }
+}
+
+impl std::ops::Not for Color {
+    type Output = Color;
+
+    fn not(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Piece {
     None,
     Pawn,
@@ -35,6 +83,19 @@ pub enum Piece {
     King,
 }
 
+//State make_move can't recover from the move itself
+#[derive(Clone, Copy, Debug)]
+struct Undo {
+    piece: Piece,
+    captured: Piece,
+    castle: BitBoard,
+    en_passant: BitBoard,
+    halfmove_clock: usize,
+    //Key of the position the move was made from, doubles as the game history
+    hash: u64,
+}
+
+#[derive(Clone)]
 pub struct Board {
     white_rooks: BitBoard,
     black_rooks: BitBoard,
@@ -57,10 +118,25 @@ pub struct Board {
     en_passant: BitBoard,
 
     white_to_play: bool,
+
+    halfmove_clock: usize,
+    fullmove_number: usize,
+
+    hash: u64,
+    //Zobrist key of the pawns alone, for the pawn structure cache
+    pawn_hash: u64,
+
+    history: Vec<Undo>,
 }
 
 impl Board {
-    fn new() -> Self {
+    /// The starting position.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //No pieces at all, for building a position up from FEN
+    fn empty() -> Self {
         Board {
             black_pawns: 0,
             black_knights: 0,
@@ -83,9 +159,42 @@ impl Board {
             en_passant: 0,
 
             white_to_play: true,
+
+            halfmove_clock: 0,
+            fullmove_number: 1,
+
+            hash: 0,
+            pawn_hash: 0,
+
+            history: Vec::new(),
         }
     }
 
+    pub fn side_to_move(&self) -> Color {
+        Color::from_white(self.white_to_play)
+    }
+
+    /// Replaces the contents of `moves` with the legal moves of the side to move.
+    pub fn legal_moves(&self, moves: &mut MoveList) {
+        moves.clear();
+        if self.white_to_play {
+            self.generate_moves::<true>(moves)
+        } else {
+            self.generate_moves::<false>(moves)
+        }
+    }
+
+    /// Whether the side to move is in check.
+    pub fn is_check(&self) -> bool {
+        if self.white_to_play {
+            self.checkmask::<true>() != BitBoard::MAX
+        } else {
+            self.checkmask::<false>() != BitBoard::MAX
+        }
+    }
+
+    /// The original material and attacked squares count in pawns, from
+    /// white's point of view. Superseded by `evaluate`, kept for comparison.
     #[inline]
     pub fn hueristic(&self) -> f32 {
         let mut total: f32 = 0.;
@@ -98,255 +207,265 @@ impl Board {
             (self.white_knights.count_ones() as f32 - self.black_knights.count_ones() as f32) * 3.;
         total += (self.white_pawns.count_ones() as f32 - self.black_pawns.count_ones() as f32) * 1.;
 
-        total += self.under_attack_by_white().count_ones() as f32 * 0.25;
-        total -= self.under_attack_by_black().count_ones() as f32 * 0.25;
+        total += self.under_attack_by(true).count_ones() as f32 * 0.25;
+        total -= self.under_attack_by(false).count_ones() as f32 * 0.25;
         total
     }
 
-    pub fn what_black_piece(&self, square: usize) -> (Piece, usize) {
+    //Piece of the given colour on `square` and its value for move ordering
+    fn what_piece(&self, white: bool, square: usize) -> (Piece, usize) {
         let mask = (1 as BitBoard) << square;
-        if mask & self.black_pawns != 0 {
+        if mask & self.pieces(white, Piece::Pawn) != 0 {
             (Piece::Pawn, 100)
-        } else if mask & self.black_knights != 0 {
+        } else if mask & self.pieces(white, Piece::Knight) != 0 {
             (Piece::Knight, 200)
-        } else if mask & self.black_bishops != 0 {
+        } else if mask & self.pieces(white, Piece::Bishop) != 0 {
             (Piece::Bishop, 300)
-        } else if mask & self.black_rooks != 0 {
+        } else if mask & self.pieces(white, Piece::Rook) != 0 {
             (Piece::Rook, 400)
-        } else if mask & self.black_queens != 0 {
+        } else if mask & self.pieces(white, Piece::Queen) != 0 {
             (Piece::Queen, 500)
         } else {
             panic!()
         }
     }
 
-    pub fn what_white_piece(&self, square: usize) -> (Piece, usize) {
-        let mask = (1 as BitBoard) << square;
-        if mask & self.white_pawns != 0 {
-            (Piece::Pawn, 100)
-        } else if mask & self.white_knights != 0 {
-            (Piece::Knight, 200)
-        } else if mask & self.white_bishops != 0 {
-            (Piece::Bishop, 300)
-        } else if mask & self.white_rooks != 0 {
-            (Piece::Rook, 400)
-        } else if mask & self.white_queens != 0 {
-            (Piece::Queen, 500)
+    /// The piece on `square` and its colour, if there is one.
+    pub fn piece_on(&self, square: Square) -> Option<(Color, Piece)> {
+        let mask: BitBoard = 1 << square.index();
+        match self.piece_at(square.index()) {
+            Piece::None => None,
+            piece => Some((Color::from_white(mask & self.white_occupied != 0), piece)),
+        }
+    }
+
+    fn piece_at(&self, square: usize) -> Piece {
+        let mask: BitBoard = 1 << square;
+        if mask & (self.white_pawns | self.black_pawns) != 0 {
+            Piece::Pawn
+        } else if mask & (self.white_knights | self.black_knights) != 0 {
+            Piece::Knight
+        } else if mask & (self.white_bishops | self.black_bishops) != 0 {
+            Piece::Bishop
+        } else if mask & (self.white_rooks | self.black_rooks) != 0 {
+            Piece::Rook
+        } else if mask & (self.white_queens | self.black_queens) != 0 {
+            Piece::Queen
+        } else if mask & (self.white_kings | self.black_kings) != 0 {
+            Piece::King
         } else {
-            panic!()
+            Piece::None
         }
     }
 
-    pub fn make_move(&mut self, board_move: &BoardMove) {
-        let to: BitBoard = 1 << board_move.to;
-        let from: BitBoard = 1 << board_move.from;
-        let mask: BitBoard = from | to;
-        self.en_passant = 0;
-        if board_move.white {
-            match board_move.piece {
-                Piece::None => panic!(),
-                Piece::Pawn => {
-                    self.white_pawns ^= mask;
-                    if south_one(south_one(to)) == from {
-                        self.en_passant = south_one(to);
-                    }
-                }
-                Piece::Knight => self.white_knights ^= mask,
-                Piece::Bishop => self.white_bishops ^= mask,
-                Piece::Rook => {
-                    self.white_rooks ^= mask;
-                    self.castle &= !from
-                }
-                Piece::Queen => self.white_queens ^= mask,
-                Piece::King => {
-                    self.white_kings ^= mask;
-                    self.castle &= !from;
-                }
-            }
-            self.white_occupied ^= from;
-            self.white_occupied |= to;
-            self.occupied ^= from;
-            self.occupied |= to;
-            match board_move.taken {
-                Piece::None => (),
-                Piece::Pawn => {
-                    if board_move.en_passant {
-                        self.black_pawns ^= south_one(to);
-                        self.black_occupied ^= south_one(to);
-                    } else {
-                        self.black_pawns ^= to;
-                        self.black_occupied ^= to;
-                    }
-                }
-                Piece::Knight => {
-                    self.black_knights ^= to;
-                    self.black_occupied ^= to;
-                }
-                Piece::Bishop => {
-                    self.black_bishops ^= to;
-                    self.black_occupied ^= to;
-                }
-                Piece::Rook => {
-                    self.black_rooks ^= to;
-                    self.black_occupied ^= to;
-                }
-                Piece::Queen => {
-                    self.black_queens ^= to;
-                    self.black_occupied ^= to;
-                }
-                Piece::King => panic!(),
-            }
-            self.white_to_play = false;
+    #[inline]
+    fn pieces(&self, white: bool, piece: Piece) -> BitBoard {
+        match (white, piece) {
+            (true, Piece::Pawn) => self.white_pawns,
+            (true, Piece::Knight) => self.white_knights,
+            (true, Piece::Bishop) => self.white_bishops,
+            (true, Piece::Rook) => self.white_rooks,
+            (true, Piece::Queen) => self.white_queens,
+            (true, Piece::King) => self.white_kings,
+            (false, Piece::Pawn) => self.black_pawns,
+            (false, Piece::Knight) => self.black_knights,
+            (false, Piece::Bishop) => self.black_bishops,
+            (false, Piece::Rook) => self.black_rooks,
+            (false, Piece::Queen) => self.black_queens,
+            (false, Piece::King) => self.black_kings,
+            (_, Piece::None) => panic!(),
+        }
+    }
+
+    #[inline]
+    fn occupied_by(&self, white: bool) -> BitBoard {
+        if white {
+            self.white_occupied
         } else {
-            match board_move.piece {
-                Piece::None => panic!(),
-                Piece::Pawn => {
-                    self.black_pawns ^= mask;
-                    if north_one(north_one(to)) == from {
-                        self.en_passant = north_one(to);
-                    }
-                }
-                Piece::Knight => self.black_knights ^= mask,
-                Piece::Bishop => self.black_bishops ^= mask,
-                Piece::Rook => {
-                    self.black_rooks ^= mask;
-                    self.castle &= !from
-                }
-                Piece::Queen => self.black_queens ^= mask,
-                Piece::King => {
-                    self.black_kings ^= mask;
-                    self.castle &= !from
-                }
-            }
-            self.black_occupied ^= from;
-            self.black_occupied |= to;
-            self.occupied ^= from;
-            self.occupied |= to;
-            match board_move.taken {
-                Piece::None => (),
-                Piece::Pawn => {
-                    if board_move.en_passant {
-                        self.white_pawns ^= north_one(to);
-                        self.white_occupied ^= north_one(to);
-                    } else {
-                        self.white_pawns ^= to;
-                        self.white_occupied ^= to;
-                    }
-                }
-                Piece::Knight => {
-                    self.white_knights ^= to;
-                    self.white_occupied ^= to;
-                }
-                Piece::Bishop => {
-                    self.white_bishops ^= to;
-                    self.white_occupied ^= to;
-                }
-                Piece::Rook => {
-                    self.white_rooks ^= to;
-                    self.white_occupied ^= to;
-                }
-                Piece::Queen => {
-                    self.white_queens ^= to;
-                    self.white_occupied ^= to;
-                }
-                Piece::King => panic!(),
-            }
-            self.white_to_play = true;
+            self.black_occupied
+        }
+    }
+
+    #[inline]
+    fn bitboard_mut(&mut self, white: bool, piece: Piece) -> &mut BitBoard {
+        match (white, piece) {
+            (true, Piece::Pawn) => &mut self.white_pawns,
+            (true, Piece::Knight) => &mut self.white_knights,
+            (true, Piece::Bishop) => &mut self.white_bishops,
+            (true, Piece::Rook) => &mut self.white_rooks,
+            (true, Piece::Queen) => &mut self.white_queens,
+            (true, Piece::King) => &mut self.white_kings,
+            (false, Piece::Pawn) => &mut self.black_pawns,
+            (false, Piece::Knight) => &mut self.black_knights,
+            (false, Piece::Bishop) => &mut self.black_bishops,
+            (false, Piece::Rook) => &mut self.black_rooks,
+            (false, Piece::Queen) => &mut self.black_queens,
+            (false, Piece::King) => &mut self.black_kings,
+            (_, Piece::None) => panic!(),
         }
     }
 
-    pub fn undo_move(&mut self, board_move: &BoardMove) {
-        let to: BitBoard = 1 << board_move.to;
-        let from: BitBoard = 1 << board_move.from;
-        let mask: BitBoard = from | to;
-        self.en_passant = board_move.last_en_passant;
-        self.castle = board_move.last_castle;
-        self.occupied = board_move.last_occupied;
-        self.white_occupied = board_move.last_white_occupied;
-        self.black_occupied = board_move.last_black_occupied;
-        if board_move.white {
-            self.white_to_play = true;
-            match board_move.piece {
-                Piece::None => panic!(),
-                Piece::Pawn => self.white_pawns ^= mask,
-                Piece::Knight => self.white_knights ^= mask,
-                Piece::Bishop => self.white_bishops ^= mask,
-                Piece::Rook => self.white_rooks ^= mask,
-                Piece::Queen => self.white_queens ^= mask,
-                Piece::King => self.white_kings ^= mask,
+    //Adds or removes a piece, keeping the colour's occupied set and the hash in step
+    #[inline]
+    fn flip(&mut self, white: bool, piece: Piece, square: usize) {
+        let mask: BitBoard = 1 << square;
+        *self.bitboard_mut(white, piece) ^= mask;
+        if white {
+            self.white_occupied ^= mask;
+        } else {
+            self.black_occupied ^= mask;
+        }
+        let key = zobrist::piece_key(white, piece, square);
+        self.hash ^= key;
+        if piece == Piece::Pawn {
+            self.pawn_hash ^= key;
+        }
+    }
+
+    //Moves the pieces for `board_move`. Every change is a flip, so calling it
+    //again with the same arguments takes the move back.
+    #[inline]
+    fn move_pieces(&mut self, board_move: Move, white: bool, piece: Piece, captured: Piece) {
+        let from = board_move.from().index();
+        let to = board_move.to().index();
+        if board_move.is_castle() {
+            let (king_to, rook_to) = board_move.castle_squares();
+            self.flip(white, Piece::King, from);
+            self.flip(white, Piece::King, king_to);
+            self.flip(white, Piece::Rook, to);
+            self.flip(white, Piece::Rook, rook_to);
+        } else {
+            if captured != Piece::None {
+                self.flip(!white, captured, captured_square(board_move, white));
             }
-            match board_move.taken {
-                Piece::None => (),
-                Piece::Pawn => {
-                    if board_move.en_passant {
-                        self.black_pawns |= south_one(to)
-                    } else {
-                        self.black_pawns |= to
-                    }
-                }
-                Piece::Knight => self.black_knights |= to,
-                Piece::Bishop => self.black_bishops |= to,
-                Piece::Rook => self.black_rooks |= to,
-                Piece::Queen => self.black_queens |= to,
-                Piece::King => self.black_kings |= to,
+            self.flip(white, piece, from);
+            match board_move.promotion() {
+                Piece::None => self.flip(white, piece, to),
+                promotion => self.flip(white, promotion, to),
             }
+        }
+        self.occupied = self.white_occupied | self.black_occupied;
+    }
+
+    /// Plays `board_move`, which has to be legal in this position.
+    pub fn make_move(&mut self, board_move: Move) {
+        let white = self.white_to_play;
+        let piece = self.piece_at(board_move.from().index());
+        let captured = if board_move.is_en_passant() {
+            Piece::Pawn
+        } else if board_move.is_capture() {
+            self.piece_at(board_move.to().index())
         } else {
-            self.white_to_play = false;
-            match board_move.piece {
-                Piece::None => panic!(),
-                Piece::Pawn => self.black_pawns ^= mask,
-                Piece::Knight => self.black_knights ^= mask,
-                Piece::Bishop => self.black_bishops ^= mask,
-                Piece::Rook => self.black_rooks ^= mask,
-                Piece::Queen => self.black_queens ^= mask,
-                Piece::King => self.black_kings ^= mask,
-            }
-            match board_move.taken {
-                Piece::None => (),
-                Piece::Pawn => {
-                    if board_move.en_passant {
-                        self.white_pawns |= north_one(to)
-                    } else {
-                        self.white_pawns |= to
-                    }
+            Piece::None
+        };
+        self.history.push(Undo {
+            piece,
+            captured,
+            castle: self.castle,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            hash: self.hash,
+        });
+
+        self.hash ^= zobrist::castle_key(self.castle) ^ self.en_passant_key() ^ zobrist::side_key();
+        self.move_pieces(board_move, white, piece, captured);
+        //Moving a king or rook, or taking a rook, loses the rights it was part of
+        self.castle &= !(1 << board_move.from().index());
+        if captured != Piece::None {
+            self.castle &= !(1 << captured_square(board_move, white));
+        }
+        self.en_passant = if board_move.is_double_push() {
+            1 << ((board_move.from().index() + board_move.to().index()) / 2)
+        } else {
+            0
+        };
+        if piece == Piece::Pawn || captured != Piece::None {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if !white {
+            self.fullmove_number += 1;
+        }
+        self.white_to_play = !white;
+        self.hash ^= zobrist::castle_key(self.castle) ^ self.en_passant_key();
+    }
+
+    /// Takes back `board_move`, which must be the last move made.
+    pub fn undo_move(&mut self, board_move: Move) {
+        let undo = self.history.pop().expect("no move to undo");
+        let white = !self.white_to_play;
+        self.move_pieces(board_move, white, undo.piece, undo.captured);
+        self.castle = undo.castle;
+        self.en_passant = undo.en_passant;
+        self.hash = undo.hash;
+        self.halfmove_clock = undo.halfmove_clock;
+        if !white {
+            self.fullmove_number -= 1;
+        }
+        self.white_to_play = white;
+    }
+
+    /// Whether the current position has occurred `n` times, counting this one.
+    /// Only positions reached by moves made on this board are known, so the
+    /// history starts at the position it was set up from.
+    pub fn is_repetition(&self, n: usize) -> bool {
+        let mut count = 1;
+        //Captures and pawn moves can't be undone, so nothing before the last
+        //one can repeat. Positions with the other side to move are skipped.
+        let reversible = self.halfmove_clock.min(self.history.len());
+        for undo in self.history[self.history.len() - reversible..]
+            .iter()
+            .rev()
+            .skip(1)
+            .step_by(2)
+        {
+            if undo.hash == self.hash {
+                count += 1;
+                if count >= n {
+                    return true;
                 }
-                Piece::Knight => self.white_knights |= to,
-                Piece::Bishop => self.white_bishops |= to,
-                Piece::Rook => self.white_rooks |= to,
-                Piece::Queen => self.white_queens |= to,
-                Piece::King => self.white_kings |= to,
             }
         }
+        count >= n
     }
+
+    /// Whether fifty moves by each side have passed without a capture or pawn move.
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    pub fn halfmove_clock(&self) -> usize {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> usize {
+        self.fullmove_number
+    }
+
+    //Number of leaf nodes `depth` plies below this position
     pub fn perft(&mut self, depth: usize) -> usize {
-        if self.white_to_play {
-            if depth == 0 {
-                return self.white_moves().len();
-            }
-            let mut total = 0;
-            for i in self.white_moves() {
-                self.make_move(&i.0);
-                total += self.perft(depth - 1);
-                self.undo_move(&i.0);
-            }
-            return total;
-        } else {
-            if depth == 0 {
-                return self.black_moves().len();
-            }
-            let mut total = 0;
-            for i in self.black_moves() {
-                self.make_move(&i.0);
-                total += self.perft(depth - 1);
-                self.undo_move(&i.0);
-            }
-            return total;
+        if depth == 0 {
+            return 1;
+        }
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        //Every legal move is a leaf, no need to make them
+        if depth == 1 {
+            return moves.len();
         }
+        let mut total = 0;
+        for board_move in moves.iter() {
+            self.make_move(board_move);
+            total += self.perft(depth - 1);
+            self.undo_move(board_move);
+        }
+        total
     }
 }
 impl Default for Board {
     fn default() -> Self {
-        Board {
+        let mut board = Board {
             white_rooks: 0x0000000000000081,
             black_rooks: 0x8100000000000000,
             white_knights: 0x0000000000000042,
@@ -368,51 +487,28 @@ impl Default for Board {
             en_passant: 0,
 
             white_to_play: true,
-        }
-    }
-}
 
-impl BoardMove {
-    pub fn readable(&self) -> String {
-        let from = square_string(self.from);
-        let to = square_string(self.to);
-        let piece = match self.piece {
-            Piece::None => "None",
-            Piece::Pawn => "Pawn",
-            Piece::Knight => "Knight",
-            Piece::Bishop => "Bishop",
-            Piece::Rook => "Rook",
-            Piece::Queen => "Queen",
-            Piece::King => "King",
+            halfmove_clock: 0,
+            fullmove_number: 1,
+
+            hash: 0,
+            pawn_hash: 0,
+
+            history: Vec::new(),
         };
-        let turn = if self.white { "White" } else { "Black" };
-        format!("{} moved {} from {} to {} ", turn, piece, from, to)
+        board.hash = board.compute_hash();
+        board.pawn_hash = board.compute_pawn_hash();
+        board
     }
 }
 
-fn square_string(square: usize) -> String {
-    let mut res = String::new();
-    match square % 8 {
-        0 => res.push('a'),
-        1 => res.push('b'),
-        2 => res.push('c'),
-        3 => res.push('d'),
-        4 => res.push('e'),
-        5 => res.push('f'),
-        6 => res.push('g'),
-        7 => res.push('h'),
-        _ => panic!(),
-    };
-    match square / 8 {
-        0 => res.push('1'),
-        1 => res.push('2'),
-        2 => res.push('3'),
-        3 => res.push('4'),
-        4 => res.push('5'),
-        5 => res.push('6'),
-        6 => res.push('7'),
-        7 => res.push('8'),
-        _ => panic!(),
-    };
-    res
-}
\ No newline at end of file
+//Square of the piece a capture removes, which differs from the destination for en passant
+#[inline]
+fn captured_square(board_move: Move, white: bool) -> usize {
+    let to = board_move.to().index();
+    match (board_move.is_en_passant(), white) {
+        (false, _) => to,
+        (true, true) => to - 8,
+        (true, false) => to + 8,
+    }
+}
@@ -0,0 +1,205 @@
+use super::moves::*;
+use super::pseudomoves::*;
+use super::utils::*;
+use super::{BitBoard, Board, Piece};
+
+impl Board {
+    //Legal moves for the side given by `WHITE`, which must be the side to move
+    #[inline]
+    pub(super) fn generate_moves<const WHITE: bool>(&self, res: &mut MoveList) {
+        let checkmask = self.checkmask::<WHITE>();
+        let pinmask_d = self.pinmask_d::<WHITE>();
+        let pinmask_hv = self.pinmask_hv::<WHITE>();
+        let pinmask = pinmask_d | pinmask_hv;
+        let enemy = self.occupied_by(!WHITE);
+        //Unpinned rook moves
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Rook) & !pinmask) {
+            let moves = rook_moves(i, !self.occupied) & checkmask;
+            self.push_moves::<WHITE>(i, moves, 2, res);
+        }
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Rook) & pinmask_hv) {
+            let moves = rook_moves(i, !self.occupied) & checkmask & pinmask_hv;
+            self.push_moves::<WHITE>(i, moves, 2, res);
+        }
+
+        //Unpinned Bishop Moves
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Bishop) & !pinmask) {
+            let moves = bishop_moves(i, !self.occupied) & checkmask;
+            self.push_moves::<WHITE>(i, moves, 3, res);
+        }
+        //Pinned bishop moves
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Bishop) & pinmask_d) {
+            let moves = bishop_moves(i, !self.occupied) & checkmask & pinmask_d;
+            self.push_moves::<WHITE>(i, moves, 3, res);
+        }
+
+        //A pinned knight can't move
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Knight) & !pinmask) {
+            let moves = KNIGHT_MOVES[i] & checkmask;
+            self.push_moves::<WHITE>(i, moves, 4, res);
+        }
+
+        // Queen moves
+        // Unpinned queen rook moves
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Queen) & !pinmask) {
+            let moves = rook_moves(i, !self.occupied) & checkmask;
+            self.push_moves::<WHITE>(i, moves, 1, res);
+        }
+        //Pinned queen rook moves
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Queen) & pinmask_hv) {
+            let moves = rook_moves(i, !self.occupied) & checkmask & pinmask_hv;
+            self.push_moves::<WHITE>(i, moves, 1, res);
+        }
+        //Unpinned queen Bishops
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Queen) & !pinmask) {
+            let moves = bishop_moves(i, !self.occupied) & checkmask;
+            self.push_moves::<WHITE>(i, moves, 1, res);
+        }
+        //Pinned queen bishop moves
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Queen) & pinmask_d) {
+            let moves = bishop_moves(i, !self.occupied) & checkmask & pinmask_d;
+            self.push_moves::<WHITE>(i, moves, 1, res);
+        }
+        let king_square = self.pieces(WHITE, Piece::King).trailing_zeros() as usize;
+        let under_attack = self.under_attack_by(!WHITE);
+        let moves = KING_MOVES[king_square] & !under_attack;
+        self.push_moves::<WHITE>(king_square, moves, 0, res);
+
+        //Pawns
+        let side = !WHITE as usize;
+        //Unpinned Pawn Pushes
+        for i in BitBoardIter(
+            self.pieces(WHITE, Piece::Pawn) & !pinmask & backward::<WHITE>(!self.occupied),
+        ) {
+            let moves = PAWN_MOVES[side][i] & checkmask & !self.occupied;
+            for m in BitBoardIter(moves) {
+                if (1 << m) & PROMOTION_RANK[side] != 0 {
+                    res.push(Move::new(i, m, QUEEN_PROMOTION), 1000);
+                    res.push(Move::new(i, m, ROOK_PROMOTION), 900);
+                    res.push(Move::new(i, m, BISHOP_PROMOTION), 800);
+                    res.push(Move::new(i, m, KNIGHT_PROMOTION), 700);
+                } else {
+                    res.push(Move::new(i, m, push_flags(i, m)), 5);
+                }
+            }
+        }
+        //Pinned pawn pushes
+        for i in BitBoardIter(
+            self.pieces(WHITE, Piece::Pawn) & pinmask_hv & backward::<WHITE>(!self.occupied),
+        ) {
+            let moves = PAWN_MOVES[side][i] & checkmask & !self.occupied & pinmask_hv;
+            for m in BitBoardIter(moves) {
+                res.push(Move::new(i, m, push_flags(i, m)), 5);
+            }
+        }
+        //Unpinned Pawn Attacks
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Pawn) & !pinmask) {
+            let moves = PAWN_ATTACKS[side][i] & checkmask & enemy;
+            self.push_pawn_captures::<WHITE>(i, moves, res);
+        }
+        //Pinned Pawn Attacks
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Pawn) & pinmask_d) {
+            let moves = PAWN_ATTACKS[side][i] & checkmask & enemy & pinmask_d;
+            self.push_pawn_captures::<WHITE>(i, moves, res);
+        }
+        //Taking the pawn that gives check also gets out of it
+        let ep_checkmask = if checkmask & backward::<WHITE>(self.en_passant) != 0 {
+            checkmask | self.en_passant
+        } else {
+            checkmask
+        };
+        //Unpinned En Passant
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Pawn) & !pinmask) {
+            let moves = PAWN_ATTACKS[side][i] & ep_checkmask & self.en_passant;
+            for m in BitBoardIter(moves) {
+                if self.en_passant_exposes_king::<WHITE>(i) {
+                    continue;
+                }
+                res.push(Move::new(i, m, EN_PASSANT), 105);
+            }
+        }
+        //Pinned En Passant
+        for i in BitBoardIter(self.pieces(WHITE, Piece::Pawn) & pinmask_d) {
+            let moves = PAWN_ATTACKS[side][i] & ep_checkmask & self.en_passant & pinmask_d;
+            for m in BitBoardIter(moves) {
+                res.push(Move::new(i, m, EN_PASSANT), 105);
+            }
+        }
+
+        //Castleing, black's squares are white's moved up seven ranks
+        let shift = if WHITE { 0 } else { 56 };
+        if (self.castle & 0x90 << shift == 0x90 << shift)
+            && under_attack & 0x70 << shift == 0
+            && self.occupied & 0x60 << shift == 0
+        {
+            res.push(Move::new(4 + shift, 7 + shift, KING_CASTLE), 0);
+        }
+
+        if (self.castle & 0x11 << shift == 0x11 << shift)
+            && under_attack & 0x1c << shift == 0
+            && self.occupied & 0xe << shift == 0
+        {
+            res.push(Move::new(4 + shift, shift, QUEEN_CASTLE), 0);
+        }
+    }
+
+    //Quiet moves and captures of a piece on `from` to the squares in `moves`
+    #[inline]
+    fn push_moves<const WHITE: bool>(
+        &self,
+        from: usize,
+        moves: BitBoard,
+        score: usize,
+        res: &mut MoveList,
+    ) {
+        for m in BitBoardIter(moves & !self.occupied) {
+            res.push(Move::new(from, m, QUIET), score);
+        }
+        for m in BitBoardIter(moves & self.occupied_by(!WHITE)) {
+            let piece = self.what_piece(!WHITE, m);
+            res.push(Move::new(from, m, CAPTURE), piece.1 + score);
+        }
+    }
+
+    #[inline]
+    fn push_pawn_captures<const WHITE: bool>(
+        &self,
+        from: usize,
+        moves: BitBoard,
+        res: &mut MoveList,
+    ) {
+        for m in BitBoardIter(moves) {
+            let piece = self.what_piece(!WHITE, m);
+            if (1 << m) & PROMOTION_RANK[!WHITE as usize] != 0 {
+                res.push(Move::new(from, m, QUEEN_PROMOTION_CAPTURE), piece.1 + 1000);
+                res.push(Move::new(from, m, ROOK_PROMOTION_CAPTURE), piece.1 + 900);
+                res.push(Move::new(from, m, BISHOP_PROMOTION_CAPTURE), piece.1 + 800);
+                res.push(Move::new(from, m, KNIGHT_PROMOTION_CAPTURE), piece.1 + 700);
+            } else {
+                res.push(Move::new(from, m, CAPTURE), piece.1 + 5);
+            }
+        }
+    }
+}
+
+//Indexed like PAWN_MOVES, white first
+const PROMOTION_RANK: [BitBoard; 2] = [0xFF00000000000000, 0x00000000000000FF];
+
+#[inline]
+const fn push_flags(from: usize, to: usize) -> u16 {
+    if from.abs_diff(to) == 16 {
+        DOUBLE_PUSH
+    } else {
+        QUIET
+    }
+}
+
+//One rank towards the given side's own back rank
+#[inline]
+pub(super) const fn backward<const WHITE: bool>(set: BitBoard) -> BitBoard {
+    if WHITE {
+        south_one(set)
+    } else {
+        north_one(set)
+    }
+}
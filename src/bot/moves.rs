@@ -0,0 +1,267 @@
+use std::fmt;
+use std::ops::Index;
+
+use super::{Board, Piece, Square};
+
+pub const MAX_MOVES: usize = 256;
+
+//Move flags, stored in the top four bits. Bit 2 marks captures and bit 3
+//promotions, the low two bits of a promotion select the piece.
+pub const QUIET: u16 = 0;
+pub const DOUBLE_PUSH: u16 = 1;
+pub const KING_CASTLE: u16 = 2;
+pub const QUEEN_CASTLE: u16 = 3;
+pub const CAPTURE: u16 = 4;
+pub const EN_PASSANT: u16 = 5;
+pub const KNIGHT_PROMOTION: u16 = 8;
+pub const BISHOP_PROMOTION: u16 = 9;
+pub const ROOK_PROMOTION: u16 = 10;
+pub const QUEEN_PROMOTION: u16 = 11;
+pub const KNIGHT_PROMOTION_CAPTURE: u16 = 12;
+pub const BISHOP_PROMOTION_CAPTURE: u16 = 13;
+pub const ROOK_PROMOTION_CAPTURE: u16 = 14;
+pub const QUEEN_PROMOTION_CAPTURE: u16 = 15;
+
+/// A move packed into 16 bits: from square, to square and flags. Castling is
+/// encoded as the king moving onto its own rook.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+impl Move {
+    //a1a1 is never a legal move, so zero is free to mean no move
+    pub const NULL: Move = Move(0);
+
+    #[inline]
+    pub(super) const fn new(from: usize, to: usize, flags: u16) -> Move {
+        Move(from as u16 | (to as u16) << 6 | flags << 12)
+    }
+
+    #[inline]
+    pub const fn from_raw(raw: u16) -> Move {
+        Move(raw)
+    }
+
+    #[inline]
+    pub const fn raw(self) -> u16 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn from(self) -> Square {
+        Square::from_index_unchecked((self.0 & 0x3f) as usize)
+    }
+
+    //Castling moves go to the rook's square
+    #[inline]
+    pub const fn to(self) -> Square {
+        Square::from_index_unchecked((self.0 >> 6 & 0x3f) as usize)
+    }
+
+    #[inline]
+    pub const fn flags(self) -> u16 {
+        self.0 >> 12
+    }
+
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub const fn is_capture(self) -> bool {
+        self.flags() & CAPTURE != 0
+    }
+
+    #[inline]
+    pub const fn is_promotion(self) -> bool {
+        self.flags() & KNIGHT_PROMOTION != 0
+    }
+
+    #[inline]
+    pub const fn is_castle(self) -> bool {
+        matches!(self.flags(), KING_CASTLE | QUEEN_CASTLE)
+    }
+
+    #[inline]
+    pub const fn is_en_passant(self) -> bool {
+        self.flags() == EN_PASSANT
+    }
+
+    #[inline]
+    pub const fn is_double_push(self) -> bool {
+        self.flags() == DOUBLE_PUSH
+    }
+
+    #[inline]
+    pub fn promotion(self) -> Piece {
+        if !self.is_promotion() {
+            return Piece::None;
+        }
+        match self.flags() & 3 {
+            0 => Piece::Knight,
+            1 => Piece::Bishop,
+            2 => Piece::Rook,
+            _ => Piece::Queen,
+        }
+    }
+
+    //Destination squares of the king and the rook for a castling move
+    #[inline]
+    pub(super) fn castle_squares(self) -> (usize, usize) {
+        let from = self.from().index();
+        if self.flags() == KING_CASTLE {
+            (from + 2, from + 1)
+        } else {
+            (from - 2, from - 1)
+        }
+    }
+
+    //Where the moving piece ends up, the king's square for castling
+    #[inline]
+    pub(super) fn destination(self) -> Square {
+        if self.is_castle() {
+            Square::from_index_unchecked(self.castle_squares().0)
+        } else {
+            self.to()
+        }
+    }
+
+    /// Long algebraic notation as used by UCI, such as `e2e4` or `e7e8q`.
+    /// Castling is written as the king's move, `e1g1`.
+    pub fn to_uci(self) -> String {
+        if self.is_null() {
+            return "0000".to_string();
+        }
+        let mut res = format!("{}{}", self.from(), self.destination());
+        match self.promotion() {
+            Piece::Knight => res.push('n'),
+            Piece::Bishop => res.push('b'),
+            Piece::Rook => res.push('r'),
+            Piece::Queen => res.push('q'),
+            _ => (),
+        }
+        res
+    }
+}
+
+/// Why a UCI move string could not be turned into a move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UciMoveError {
+    /// Not two squares followed by an optional promotion piece.
+    Syntax,
+    /// Well formed, but not a legal move in the position.
+    Illegal,
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UciMoveError::Syntax => write!(f, "invalid UCI move"),
+            UciMoveError::Illegal => write!(f, "illegal move"),
+        }
+    }
+}
+
+impl std::error::Error for UciMoveError {}
+
+impl Board {
+    /// Finds the legal move written in UCI long algebraic notation.
+    pub fn parse_uci_move(&self, uci: &str) -> Result<Move, UciMoveError> {
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(UciMoveError::Syntax);
+        }
+        let from: Square = uci[0..2].parse().map_err(|_| UciMoveError::Syntax)?;
+        let to: Square = uci[2..4].parse().map_err(|_| UciMoveError::Syntax)?;
+        let promotion = match uci.as_bytes().get(4) {
+            None => Piece::None,
+            Some(b'n') => Piece::Knight,
+            Some(b'b') => Piece::Bishop,
+            Some(b'r') => Piece::Rook,
+            Some(b'q') => Piece::Queen,
+            Some(_) => return Err(UciMoveError::Syntax),
+        };
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        let found = moves
+            .iter()
+            .find(|m| m.from() == from && m.destination() == to && m.promotion() == promotion);
+        found.ok_or(UciMoveError::Illegal)
+    }
+}
+
+impl fmt::Debug for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Move({})", self.to_uci())
+    }
+}
+
+/// Fixed capacity list of moves with their ordering scores, filled by the
+/// move generator without allocating.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [(Move, usize); MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        MoveList {
+            moves: [(Move::NULL, 0); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, board_move: Move, score: usize) {
+        self.moves[self.len] = (board_move, score);
+        self.len += 1;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[(Move, usize)] {
+        &self.moves[..self.len]
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [(Move, usize)] {
+        &mut self.moves[..self.len]
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Move> + '_ {
+        self.as_slice().iter().map(|(board_move, _)| *board_move)
+    }
+
+    pub fn contains(&self, board_move: Move) -> bool {
+        self.iter().any(|m| m == board_move)
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for MoveList {
+    type Output = (Move, usize);
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.as_slice()[index]
+    }
+}
@@ -0,0 +1,84 @@
+use super::{BitBoard, Board, Color, MoveList};
+
+const LIGHT_SQUARES: BitBoard = 0x55AA55AA55AA55AA;
+
+/// State of the game in a position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Ongoing,
+    /// The side to move is mated, the given colour won.
+    Checkmate(Color),
+    Stalemate,
+    /// A hundred plies without a capture or pawn move.
+    FiftyMoveRule,
+    ThreefoldRepetition,
+    /// Neither side has the material to checkmate.
+    InsufficientMaterial,
+}
+
+impl Outcome {
+    pub fn is_game_over(self) -> bool {
+        self != Outcome::Ongoing
+    }
+
+    pub fn is_draw(self) -> bool {
+        matches!(
+            self,
+            Outcome::Stalemate
+                | Outcome::FiftyMoveRule
+                | Outcome::ThreefoldRepetition
+                | Outcome::InsufficientMaterial
+        )
+    }
+
+    /// The winning side, `None` for draws and ongoing games.
+    pub fn winner(self) -> Option<Color> {
+        match self {
+            Outcome::Checkmate(winner) => Some(winner),
+            _ => None,
+        }
+    }
+}
+
+impl Board {
+    //Mate on the hundredth ply still counts as mate
+    pub fn outcome(&self) -> Outcome {
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        if moves.is_empty() {
+            if self.is_check() {
+                Outcome::Checkmate(!self.side_to_move())
+            } else {
+                Outcome::Stalemate
+            }
+        } else if self.is_insufficient_material() {
+            Outcome::InsufficientMaterial
+        } else if self.is_fifty_move_draw() {
+            Outcome::FiftyMoveRule
+        } else if self.is_repetition(3) {
+            Outcome::ThreefoldRepetition
+        } else {
+            Outcome::Ongoing
+        }
+    }
+
+    /// Whether no sequence of legal moves can end in checkmate: bare kings,
+    /// a single minor piece, or only bishops that all stand on one colour.
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy = self.white_pawns
+            | self.black_pawns
+            | self.white_rooks
+            | self.black_rooks
+            | self.white_queens
+            | self.black_queens;
+        if heavy != 0 {
+            return false;
+        }
+        let knights = self.white_knights | self.black_knights;
+        let bishops = self.white_bishops | self.black_bishops;
+        if (knights | bishops).count_ones() <= 1 {
+            return true;
+        }
+        knights == 0 && (bishops & LIGHT_SQUARES == 0 || bishops & !LIGHT_SQUARES == 0)
+    }
+}
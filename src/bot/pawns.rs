@@ -0,0 +1,220 @@
+use super::eval::{s, Score};
+use super::utils::*;
+use super::{BitBoard, Board, Piece};
+
+const DOUBLED: Score = s(-10, -40);
+const ISOLATED: Score = s(-5, -15);
+const BACKWARD: Score = s(-9, -24);
+
+//Indexed by the rank counted from the pawn's own side, 0 for its back rank
+const CONNECTED: [Score; 8] = [
+    s(0, 0),
+    s(4, 2),
+    s(6, 3),
+    s(9, 5),
+    s(20, 15),
+    s(35, 30),
+    s(60, 55),
+    s(0, 0),
+];
+const PASSED: [Score; 8] = [
+    s(0, 0),
+    s(5, 10),
+    s(8, 15),
+    s(10, 25),
+    s(30, 50),
+    s(70, 110),
+    s(120, 180),
+    s(0, 0),
+];
+const CANDIDATE: [Score; 8] = [
+    s(0, 0),
+    s(3, 5),
+    s(4, 7),
+    s(6, 12),
+    s(12, 25),
+    s(25, 45),
+    s(0, 0),
+    s(0, 0),
+];
+//Endgame weight per square of distance between a king and the square in
+//front of a passed pawn, the enemy king being far away counts for more
+const ENEMY_KING_DISTANCE: i32 = 5;
+const OWN_KING_DISTANCE: i32 = 2;
+
+const PAWN_TABLE_ENTRIES: usize = 1 << 14;
+
+/// Pawn structure terms, which only depend on where the pawns are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PawnEntry {
+    key: u64,
+    pub white: PawnTerms,
+    pub black: PawnTerms,
+    pub white_passed: BitBoard,
+    pub black_passed: BitBoard,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PawnTerms {
+    pub doubled: Score,
+    pub isolated: Score,
+    pub backward: Score,
+    pub connected: Score,
+    pub passed: Score,
+    pub candidate: Score,
+}
+
+impl PawnTerms {
+    pub fn total(&self) -> Score {
+        self.doubled + self.isolated + self.backward + self.connected + self.passed + self.candidate
+    }
+}
+
+/// Cache of pawn structure evaluations indexed by the pawn key. Pawns move
+/// rarely, so most lookups during a search hit.
+pub(super) struct PawnTable {
+    entries: Vec<PawnEntry>,
+}
+
+impl PawnTable {
+    pub fn new() -> Self {
+        PawnTable {
+            entries: vec![PawnEntry::default(); PAWN_TABLE_ENTRIES],
+        }
+    }
+
+    pub fn probe(&mut self, board: &Board) -> PawnEntry {
+        let key = board.pawn_hash();
+        let entry = &mut self.entries[key as usize & (PAWN_TABLE_ENTRIES - 1)];
+        //An empty slot has key zero, which is also the key with no pawns, and
+        //no pawns scores zero anyway
+        if entry.key != key {
+            *entry = board.pawn_entry();
+        }
+        *entry
+    }
+}
+
+#[inline]
+pub(super) fn forward_one(white: bool, set: BitBoard) -> BitBoard {
+    if white {
+        north_one(set)
+    } else {
+        south_one(set)
+    }
+}
+
+//Squares strictly in front of each pawn on its file
+#[inline]
+pub(super) fn front_span(white: bool, set: BitBoard) -> BitBoard {
+    if white {
+        north_fill(north_one(set))
+    } else {
+        south_fill(south_one(set))
+    }
+}
+
+#[inline]
+pub(super) fn pawn_attacks(white: bool, set: BitBoard) -> BitBoard {
+    let forward = forward_one(white, set);
+    east_one(forward) | west_one(forward)
+}
+
+#[inline]
+fn neighbours(set: BitBoard) -> BitBoard {
+    east_one(set) | west_one(set)
+}
+
+#[inline]
+pub(super) fn relative_rank(white: bool, square: usize) -> usize {
+    if white {
+        square / 8
+    } else {
+        7 - square / 8
+    }
+}
+
+fn distance(a: usize, b: usize) -> i32 {
+    let files = (a % 8).abs_diff(b % 8);
+    let ranks = (a / 8).abs_diff(b / 8);
+    files.max(ranks) as i32
+}
+
+impl Board {
+    pub(super) fn pawn_entry(&self) -> PawnEntry {
+        let (white, white_passed) = self.pawn_terms(true);
+        let (black, black_passed) = self.pawn_terms(false);
+        PawnEntry {
+            key: self.pawn_hash(),
+            white,
+            black,
+            white_passed,
+            black_passed,
+        }
+    }
+
+    fn pawn_terms(&self, white: bool) -> (PawnTerms, BitBoard) {
+        let ours = self.pieces(white, Piece::Pawn);
+        let theirs = self.pieces(!white, Piece::Pawn);
+        let our_attacks = pawn_attacks(white, ours);
+        let their_attacks = pawn_attacks(!white, theirs);
+        //Squares an enemy pawn could still stop a pawn on, or take it from
+        let their_spans = front_span(!white, theirs | neighbours(theirs));
+        let mut terms = PawnTerms::default();
+
+        let doubled = ours & front_span(white, ours);
+        terms.doubled = DOUBLED * doubled.count_ones() as i32;
+        let isolated = ours & !neighbours(file_fill(ours));
+        terms.isolated = ISOLATED * isolated.count_ones() as i32;
+        //No pawn beside or behind to support it, and it can't step forward
+        //without being taken
+        let supportable = front_span(white, neighbours(ours)) | neighbours(ours);
+        let stop_attacked = ours & forward_one(!white, their_attacks);
+        let backward = stop_attacked & !isolated & !supportable;
+        terms.backward = BACKWARD * backward.count_ones() as i32;
+
+        //Only the front pawn of a doubled pair can be passed
+        let behind_own = ours & front_span(!white, ours);
+        let connected = ours & (our_attacks | neighbours(ours));
+        let passed = ours & !their_spans & !behind_own;
+        for square in BitBoardIter(connected) {
+            terms.connected += CONNECTED[relative_rank(white, square)];
+        }
+        for square in BitBoardIter(passed) {
+            terms.passed += PASSED[relative_rank(white, square)];
+        }
+
+        //Nothing in front, and at least as many pawns to help it through as
+        //there are enemy pawns guarding the way
+        let open = ours & !passed & !front_span(!white, theirs) & !behind_own;
+        for square in BitBoardIter(open) {
+            let pawn: BitBoard = 1 << square;
+            let ahead = front_span(white, pawn);
+            let sentries = neighbours(ahead) & theirs;
+            let helpers = neighbours(file_fill(pawn) & !ahead) & ours;
+            if helpers.count_ones() >= sentries.count_ones() {
+                terms.candidate += CANDIDATE[relative_rank(white, square)];
+            }
+        }
+        (terms, passed)
+    }
+
+    //Passed pawns are worth more with the enemy king far away from the path
+    //and our own close by. Depends on the kings so it can't be cached.
+    pub(super) fn passed_pawn_kings(&self, white: bool, passed: BitBoard) -> Score {
+        let our_king = self.pieces(white, Piece::King).trailing_zeros() as usize;
+        let their_king = self.pieces(!white, Piece::King).trailing_zeros() as usize;
+        let mut bonus = 0;
+        for square in BitBoardIter(passed) {
+            let rank = relative_rank(white, square) as i32;
+            if rank < 3 {
+                continue;
+            }
+            let stop = forward_one(white, 1 << square).trailing_zeros() as usize;
+            bonus += (rank - 2)
+                * (ENEMY_KING_DISTANCE * distance(their_king, stop)
+                    - OWN_KING_DISTANCE * distance(our_king, stop));
+        }
+        s(0, bonus)
+    }
+}
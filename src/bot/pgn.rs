@@ -0,0 +1,630 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use super::{Board, FenError, Move, SanError, SearchResult, START_FEN};
+
+//Tags every exported game starts with, in this order, and their values when
+//unknown
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+const LINE_WIDTH: usize = 80;
+
+/// A game read from PGN. Variations are skipped, only the main line is kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    /// Tag pairs in the order they were given.
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<GameMove>,
+    /// `1-0`, `0-1`, `1/2-1/2` or `*`.
+    pub result: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameMove {
+    pub board_move: Move,
+    /// Comment following the move, without the braces.
+    pub comment: Option<String>,
+    /// Numeric annotation glyphs, with `!` and `?` suffixes turned into
+    /// their `$1` to `$6` equivalents.
+    pub nags: Vec<u8>,
+}
+
+impl Game {
+    /// An empty game from the standard start position, with no result yet.
+    pub fn new() -> Game {
+        Game {
+            tags: Vec::new(),
+            moves: Vec::new(),
+            result: "*".to_string(),
+        }
+    }
+
+    /// Replaces the value of a tag, adding the tag if it isn't there yet.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn push(&mut self, board_move: Move, comment: Option<String>) {
+        self.moves.push(GameMove {
+            board_move,
+            comment,
+            nags: Vec::new(),
+        });
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The position before the first move, from the FEN tag if there is one.
+    pub fn start_position(&self) -> Result<Board, FenError> {
+        Board::from_fen(self.tag("FEN").unwrap_or(START_FEN))
+    }
+
+    /// The start position followed by the position after each move.
+    pub fn positions(&self) -> Result<impl Iterator<Item = Board> + '_, FenError> {
+        let mut board = self.start_position()?;
+        let start = board.clone();
+        Ok(
+            std::iter::once(start).chain(self.moves.iter().map(move |game_move| {
+                board.make_move(game_move.board_move);
+                board.clone()
+            })),
+        )
+    }
+
+    /// The game in PGN export format: the Seven Tag Roster followed by any
+    /// other tags, then SAN movetext wrapped at 80 columns. Fails if the FEN
+    /// tag isn't a valid position.
+    pub fn to_pgn(&self) -> Result<String, FenError> {
+        let mut res = String::new();
+        for (name, unknown) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => &self.result,
+                _ => self.tag(name).unwrap_or(unknown),
+            };
+            push_tag(&mut res, name, value);
+        }
+        //Readers expect SetUp to say the FEN is there
+        if self.tag("FEN").is_some() && self.tag("SetUp").is_none() {
+            push_tag(&mut res, "SetUp", "1");
+        }
+        for (name, value) in &self.tags {
+            if SEVEN_TAG_ROSTER.iter().all(|(roster, _)| roster != name) {
+                push_tag(&mut res, name, value);
+            }
+        }
+        res.push('\n');
+
+        let mut words: Vec<String> = Vec::new();
+        let mut board = self.start_position()?;
+        //Black's moves only get a number at the start or after a comment
+        let mut needs_number = true;
+        for game_move in &self.moves {
+            let fullmove = board.fullmove_number();
+            if board.side_to_move().is_white() {
+                words.push(format!("{}.", fullmove));
+            } else if needs_number {
+                words.push(format!("{}...", fullmove));
+            }
+            words.push(board.to_san(game_move.board_move));
+            board.make_move(game_move.board_move);
+            needs_number = false;
+            for nag in &game_move.nags {
+                words.push(format!("${}", nag));
+            }
+            if let Some(comment) = &game_move.comment {
+                //There is no escape for the closing brace, so it has to go
+                let comment = format!("{{{}}}", comment.replace('}', ""));
+                words.extend(comment.split_whitespace().map(str::to_string));
+                needs_number = true;
+            }
+        }
+        words.push(self.result.clone());
+
+        let mut line_len = 0;
+        for word in words {
+            if line_len > 0 && line_len + 1 + word.len() > LINE_WIDTH {
+                res.push('\n');
+                line_len = 0;
+            }
+            if line_len > 0 {
+                res.push(' ');
+                line_len += 1;
+            }
+            res.push_str(&word);
+            line_len += word.len();
+        }
+        res.push('\n');
+        Ok(res)
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn push_tag(res: &mut String, name: &str, value: &str) {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    res.push_str(&format!("[{} \"{}\"]\n", name, value));
+}
+
+impl SearchResult {
+    /// The score and depth as a PGN move comment, `+0.35/12` or `-M3/9`.
+    pub fn pgn_comment(&self) -> String {
+        match self.mate_in() {
+            Some(moves) if moves > 0 => format!("+M{}/{}", moves, self.depth),
+            Some(moves) => format!("-M{}/{}", -moves, self.depth),
+            None => format!("{:+.2}/{}", self.score as f64 / 100.0, self.depth),
+        }
+    }
+}
+
+/// A malformed game and the line the problem was found on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnError {
+    pub line: usize,
+    pub kind: PgnErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PgnErrorKind {
+    /// Reading failed, nothing more is read after this.
+    Io(io::ErrorKind),
+    UnterminatedComment,
+    UnterminatedString,
+    UnterminatedVariation,
+    UnexpectedCharacter(char),
+    UnexpectedToken(String),
+    /// A tag pair that isn't `[Name "value"]`.
+    MalformedTag,
+    Fen(FenError),
+    /// A move that doesn't resolve to a legal move in the position.
+    Move(String, SanError),
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            PgnErrorKind::Io(kind) => write!(f, "{}", io::Error::from(*kind)),
+            PgnErrorKind::UnterminatedComment => write!(f, "comment is never closed"),
+            PgnErrorKind::UnterminatedString => write!(f, "string is never closed"),
+            PgnErrorKind::UnterminatedVariation => write!(f, "variation is never closed"),
+            PgnErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            PgnErrorKind::UnexpectedToken(token) => write!(f, "unexpected {}", token),
+            PgnErrorKind::MalformedTag => write!(f, "malformed tag pair"),
+            PgnErrorKind::Fen(e) => write!(f, "{}", e),
+            PgnErrorKind::Move(san, e) => write!(f, "{}: {}", san, e),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Symbol(String),
+    Str(String),
+    Comment(String),
+    Nag(u8),
+    Period,
+    Asterisk,
+    LeftBracket,
+    RightBracket,
+    LeftParen,
+    RightParen,
+}
+
+impl Token {
+    fn is_result(&self) -> bool {
+        match self {
+            Token::Symbol(s) => matches!(s.as_str(), "1-0" | "0-1" | "1/2-1/2"),
+            Token::Asterisk => true,
+            _ => false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::Symbol(s) => format!("'{}'", s),
+            Token::Str(s) => format!("string \"{}\"", s),
+            Token::Comment(_) => "comment".to_string(),
+            Token::Nag(n) => format!("${}", n),
+            Token::Period => "'.'".to_string(),
+            Token::Asterisk => "'*'".to_string(),
+            Token::LeftBracket => "'['".to_string(),
+            Token::RightBracket => "']'".to_string(),
+            Token::LeftParen => "'('".to_string(),
+            Token::RightParen => "')'".to_string(),
+        }
+    }
+}
+
+//Splits the input into tokens one line at a time
+struct Lexer<R> {
+    reader: R,
+    line: String,
+    pos: usize,
+    line_number: usize,
+    peeked: Option<Option<(Token, usize)>>,
+}
+
+impl<R: BufRead> Lexer<R> {
+    fn error(&self, kind: PgnErrorKind) -> PgnError {
+        PgnError {
+            line: self.line_number,
+            kind,
+        }
+    }
+
+    //False at the end of the input
+    fn next_line(&mut self) -> Result<bool, PgnError> {
+        self.line.clear();
+        self.pos = 0;
+        let read = self
+            .reader
+            .read_line(&mut self.line)
+            .map_err(|e| self.error(PgnErrorKind::Io(e.kind())))?;
+        if read == 0 {
+            return Ok(false);
+        }
+        self.line_number += 1;
+        //Escaped lines are for other software to interpret
+        if self.line.starts_with('%') {
+            self.pos = self.line.len();
+        }
+        Ok(true)
+    }
+
+    fn peek(&mut self) -> Result<Option<&(Token, usize)>, PgnError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lex()?);
+        }
+        Ok(self.peeked.as_ref().unwrap().as_ref())
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, usize)>, PgnError> {
+        match self.peeked.take() {
+            Some(token) => Ok(token),
+            None => self.lex(),
+        }
+    }
+
+    fn lex(&mut self) -> Result<Option<(Token, usize)>, PgnError> {
+        let c = loop {
+            let rest = &self.line[self.pos..];
+            match rest.char_indices().find(|(_, c)| !c.is_whitespace()) {
+                Some((i, c)) => {
+                    self.pos += i + c.len_utf8();
+                    break c;
+                }
+                None if self.next_line()? => continue,
+                None => return Ok(None),
+            }
+        };
+        let line = self.line_number;
+        let token = match c {
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '.' => Token::Period,
+            '*' => Token::Asterisk,
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    let rest = &self.line[self.pos..];
+                    if let Some(end) = rest.find('}') {
+                        comment.push_str(&rest[..end]);
+                        self.pos += end + 1;
+                        break;
+                    }
+                    comment.push_str(rest);
+                    if !self.next_line()? {
+                        return Err(PgnError {
+                            line,
+                            kind: PgnErrorKind::UnterminatedComment,
+                        });
+                    }
+                    //Escape lines inside a comment are still comment text
+                    self.pos = 0;
+                }
+                Token::Comment(comment.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            ';' => {
+                let comment = self.line[self.pos..].trim().to_string();
+                self.pos = self.line.len();
+                Token::Comment(comment)
+            }
+            '"' => {
+                let mut value = String::new();
+                let mut chars = self.line[self.pos..].char_indices();
+                loop {
+                    match chars.next() {
+                        Some((i, '"')) => {
+                            self.pos += i + 1;
+                            break;
+                        }
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => value.push(escaped),
+                            None => return Err(self.error(PgnErrorKind::UnterminatedString)),
+                        },
+                        Some((_, '\n')) | None => {
+                            return Err(self.error(PgnErrorKind::UnterminatedString))
+                        }
+                        Some((_, c)) => value.push(c),
+                    }
+                }
+                Token::Str(value)
+            }
+            '$' => {
+                let rest = &self.line[self.pos..];
+                let digits = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let nag = rest[..digits]
+                    .parse()
+                    .map_err(|_| self.error(PgnErrorKind::UnexpectedCharacter('$')))?;
+                self.pos += digits;
+                Token::Nag(nag)
+            }
+            '!' | '?' => {
+                let start = self.pos - 1;
+                let rest = &self.line[self.pos..];
+                self.pos += rest.find(|c| c != '!' && c != '?').unwrap_or(rest.len());
+                let nag = match &self.line[start..self.pos] {
+                    "!" => 1,
+                    "?" => 2,
+                    "!!" => 3,
+                    "??" => 4,
+                    "!?" => 5,
+                    "?!" => 6,
+                    _ => return Err(self.error(PgnErrorKind::UnexpectedCharacter(c))),
+                };
+                Token::Nag(nag)
+            }
+            c if c.is_ascii_alphanumeric() => {
+                let start = self.pos - 1;
+                let rest = &self.line[self.pos..];
+                self.pos += rest
+                    .find(|c: char| !c.is_ascii_alphanumeric() && !"_+#=:-/".contains(c))
+                    .unwrap_or(rest.len());
+                Token::Symbol(self.line[start..self.pos].to_string())
+            }
+            c => return Err(self.error(PgnErrorKind::UnexpectedCharacter(c))),
+        };
+        Ok(Some((token, line)))
+    }
+}
+
+/// Reads games one at a time from PGN text. A malformed game is reported as
+/// an error and reading carries on with the game after it.
+pub struct PgnReader<R> {
+    lexer: Lexer<R>,
+    //Whether the game being read has got past its tags
+    in_moves: bool,
+    done: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        PgnReader {
+            lexer: Lexer {
+                reader,
+                line: String::new(),
+                pos: 0,
+                line_number: 0,
+                peeked: None,
+            },
+            in_moves: false,
+            done: false,
+        }
+    }
+
+    //The tag pair and the line it starts on
+    fn read_tag(&mut self) -> Result<(String, String, usize), PgnError> {
+        let (name, line) = self.expect_token()?;
+        let (value, _) = self.expect_token()?;
+        let (close, _) = self.expect_token()?;
+        match (name, value, close) {
+            (Token::Symbol(name), Token::Str(value), Token::RightBracket) => {
+                Ok((name, value, line))
+            }
+            _ => Err(PgnError {
+                line,
+                kind: PgnErrorKind::MalformedTag,
+            }),
+        }
+    }
+
+    fn expect_token(&mut self) -> Result<(Token, usize), PgnError> {
+        match self.lexer.next_token()? {
+            Some(token) => Ok(token),
+            None => Err(self
+                .lexer
+                .error(PgnErrorKind::UnexpectedToken("end of input".to_string()))),
+        }
+    }
+
+    fn skip_variation(&mut self, line: usize) -> Result<(), PgnError> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.lexer.next_token()? {
+                Some((Token::LeftParen, _)) => depth += 1,
+                Some((Token::RightParen, _)) => depth -= 1,
+                Some(_) => (),
+                None => {
+                    return Err(PgnError {
+                        line,
+                        kind: PgnErrorKind::UnterminatedVariation,
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_game(&mut self) -> Result<Option<Game>, PgnError> {
+        self.in_moves = false;
+        let mut tags = Vec::new();
+        let mut fen = None;
+        while let Some((Token::LeftBracket, _)) = self.lexer.peek()? {
+            self.lexer.next_token()?;
+            let (name, value, line) = self.read_tag()?;
+            if name == "FEN" && fen.is_none() {
+                fen = Some((value.clone(), line));
+            }
+            tags.push((name, value));
+        }
+        if tags.is_empty() && self.lexer.peek()?.is_none() {
+            return Ok(None);
+        }
+
+        let mut board = match fen {
+            Some((fen, line)) => Board::from_fen(&fen).map_err(|e| PgnError {
+                line,
+                kind: PgnErrorKind::Fen(e),
+            })?,
+            None => Board::new(),
+        };
+        let mut moves: Vec<GameMove> = Vec::new();
+        let mut result = None;
+        self.in_moves = true;
+        loop {
+            //A tag pair straight after the moves starts the next game
+            if let Some((Token::LeftBracket, _)) | None = self.lexer.peek()? {
+                break;
+            }
+            let (token, line) = self.expect_token()?;
+            match token {
+                token if token.is_result() => {
+                    result = Some(match token {
+                        Token::Symbol(s) => s,
+                        _ => "*".to_string(),
+                    });
+                    break;
+                }
+                //Move numbers
+                Token::Symbol(s) if s.bytes().all(|b| b.is_ascii_digit()) => (),
+                Token::Period => (),
+                Token::Symbol(san) => {
+                    let board_move = board.parse_san(&san).map_err(|e| PgnError {
+                        line,
+                        kind: PgnErrorKind::Move(san.clone(), e),
+                    })?;
+                    board.make_move(board_move);
+                    moves.push(GameMove {
+                        board_move,
+                        comment: None,
+                        nags: Vec::new(),
+                    });
+                }
+                //Comments before the first move have nowhere to go
+                Token::Comment(comment) => {
+                    if let Some(last) = moves.last_mut() {
+                        last.comment = Some(match last.comment.take() {
+                            Some(previous) => previous + " " + &comment,
+                            None => comment,
+                        });
+                    }
+                }
+                Token::Nag(nag) => {
+                    if let Some(last) = moves.last_mut() {
+                        last.nags.push(nag);
+                    }
+                }
+                Token::LeftParen => self.skip_variation(line)?,
+                token => {
+                    return Err(PgnError {
+                        line,
+                        kind: PgnErrorKind::UnexpectedToken(token.describe()),
+                    })
+                }
+            }
+        }
+        let result = result
+            .or_else(|| {
+                tags.iter()
+                    .find(|(name, _)| name == "Result")
+                    .map(|(_, value)| value.clone())
+            })
+            .unwrap_or_else(|| "*".to_string());
+        Ok(Some(Game {
+            tags,
+            moves,
+            result,
+        }))
+    }
+
+    //Throws away the rest of a malformed game: up to its result, or up to
+    //the tags of the next game once the moves have started
+    fn skip_game(&mut self) -> Result<(), PgnError> {
+        let mut in_tag = false;
+        loop {
+            match self.lexer.peek()? {
+                None => return Ok(()),
+                Some((Token::LeftBracket, _)) if self.in_moves => return Ok(()),
+                Some((token, _)) if token.is_result() => {
+                    self.lexer.next_token()?;
+                    return Ok(());
+                }
+                Some((Token::LeftBracket, _)) => in_tag = true,
+                Some((Token::RightBracket, _)) => in_tag = false,
+                Some(_) if !in_tag => self.in_moves = true,
+                Some(_) => (),
+            }
+            self.lexer.next_token()?;
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<Game, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_game() {
+            Ok(game) => game.map(Ok),
+            Err(e) => {
+                if let PgnErrorKind::Io(_) = e.kind {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                //Skipping can run into lexing errors of its own
+                loop {
+                    match self.skip_game() {
+                        Ok(()) => break,
+                        Err(PgnError {
+                            kind: PgnErrorKind::Io(_),
+                            ..
+                        }) => {
+                            self.done = true;
+                            break;
+                        }
+                        Err(_) => (),
+                    }
+                }
+                Some(Err(e))
+            }
+        }
+    }
+}
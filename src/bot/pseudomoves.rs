@@ -1,3 +1,4 @@
+use super::magic::{bishop_attacks, rook_attacks};
 use super::utils::*;
 
 use super::BitBoard;
@@ -122,7 +123,7 @@ pub const KNIGHT_MOVES: [BitBoard; 64] = {
     res
 };
 
-pub const PATH_BETWEEN: [[BitBoard; 64]; 64] = {
+pub static PATH_BETWEEN: [[BitBoard; 64]; 64] = {
     let mut res: [[BitBoard; 64]; 64] = [[0; 64]; 64];
     let mut from: BitBoard = 0;
     while from < 64 {
@@ -156,9 +157,9 @@ const fn path_between(from: BitBoard, to: BitBoard) -> BitBoard {
     }
 }
 
+//Plain ray scans, now only used to fill in the magic tables
 //Source: https://rhysre.net/fast-chess-move-generation-with-magic-bitboards.html
-#[inline]
-pub const fn bishop_moves(square: usize, empty: BitBoard) -> BitBoard {
+pub(super) const fn ray_bishop_moves(square: usize, empty: BitBoard) -> BitBoard {
     let blockers = !empty;
     let mut attacks: BitBoard = 0;
 
@@ -193,8 +194,7 @@ pub const fn bishop_moves(square: usize, empty: BitBoard) -> BitBoard {
 }
 
 //Source: https://rhysre.net/fast-chess-move-generation-with-magic-bitboards.html
-#[inline]
-pub const fn rook_moves(square: usize, empty: BitBoard) -> BitBoard {
+pub(super) const fn ray_rook_moves(square: usize, empty: BitBoard) -> BitBoard {
     let blockers = !empty;
     let mut attacks: BitBoard = 0;
 
@@ -230,113 +230,26 @@ pub const fn rook_moves(square: usize, empty: BitBoard) -> BitBoard {
 }
 
 #[inline]
-pub fn rook_xray(square: usize, empty: BitBoard) -> BitBoard {
-    let mut blockers = !empty;
-    let mut attacks: BitBoard = 0;
-
-    // North
-    attacks |= RAYS[0][square];
-    if RAYS[0][square] & blockers != 0 {
-        let blocker_index = BitBoard::trailing_zeros(RAYS[0][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << blocker_index);
-        if RAYS[0][square] & blockers != 0 {
-            let blocker_index = BitBoard::trailing_zeros(RAYS[0][square] & blockers) as usize;
-            attacks &= !RAYS[0][blocker_index];
-        }
-    }
-
-    //East
-    attacks |= RAYS[2][square];
-    if RAYS[2][square] & blockers != 0 {
-        let blocker_index = BitBoard::trailing_zeros(RAYS[2][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << blocker_index);
-        if RAYS[2][square] & blockers != 0 {
-            let blocker_index = BitBoard::trailing_zeros(RAYS[2][square] & blockers) as usize;
-            attacks &= !RAYS[2][blocker_index];
-        }
-    }
-
-    //South
-    attacks |= RAYS[4][square];
-    if RAYS[4][square] & blockers != 0 {
-        let blocker_index = BitBoard::leading_zeros(RAYS[4][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << 63 - blocker_index);
-        if RAYS[4][square] & blockers != 0 {
-            let blocker_index = BitBoard::leading_zeros(RAYS[4][square] & blockers) as usize;
-            attacks &= !RAYS[4][63 - blocker_index];
-        }
-    }
-
-    //West
-    attacks |= RAYS[6][square];
-    if RAYS[6][square] & blockers != 0 {
-        let blocker_index = BitBoard::leading_zeros(RAYS[6][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << 63 - blocker_index);
-        if RAYS[6][square] & blockers != 0 {
-            let blocker_index = BitBoard::leading_zeros(RAYS[6][square] & blockers) as usize;
-            attacks &= !RAYS[6][63 - blocker_index];
-        }
-    }
-
-    attacks
+pub fn bishop_moves(square: usize, empty: BitBoard) -> BitBoard {
+    bishop_attacks(square, !empty)
 }
 
 #[inline]
-pub fn bishop_xray(square: usize, empty: BitBoard) -> BitBoard {
-    let mut blockers = !empty;
-    let mut attacks: BitBoard = 0;
-
-    // North
-    attacks |= RAYS[7][square];
-    if RAYS[7][square] & blockers != 0 {
-        let blocker_index = BitBoard::trailing_zeros(RAYS[7][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << blocker_index);
-        if RAYS[7][square] & blockers != 0 {
-            let blocker_index = BitBoard::trailing_zeros(RAYS[7][square] & blockers) as usize;
-            attacks &= !RAYS[7][blocker_index];
-        }
-    }
-
-    //East
-    attacks |= RAYS[1][square];
-    if RAYS[1][square] & blockers != 0 {
-        let blocker_index = BitBoard::trailing_zeros(RAYS[1][square] & blockers) as usize;
-
-        blockers &= !((1 as BitBoard) << blocker_index);
-        if RAYS[1][square] & blockers != 0 {
-            let blocker_index = BitBoard::trailing_zeros(RAYS[1][square] & blockers) as usize;
-            attacks &= !RAYS[1][blocker_index];
-        }
-    }
-
-    //South
-    attacks |= RAYS[3][square];
-    if RAYS[3][square] & blockers != 0 {
-        let blocker_index = BitBoard::leading_zeros(RAYS[3][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << 63 - blocker_index);
-        if RAYS[3][square] & blockers != 0 {
-            let blocker_index = BitBoard::leading_zeros(RAYS[3][square] & blockers) as usize;
-            attacks &= !RAYS[3][63 - blocker_index];
-        }
-    }
-
-    //West
-    attacks |= RAYS[5][square];
-    if RAYS[5][square] & blockers != 0 {
-        let blocker_index = BitBoard::leading_zeros(RAYS[5][square] & blockers) as usize;
-        blockers &= !((1 as BitBoard) << 63 - blocker_index);
-        if RAYS[5][square] & blockers != 0 {
-            let blocker_index = BitBoard::leading_zeros(RAYS[5][square] & blockers) as usize;
-            attacks &= !RAYS[5][63 - blocker_index];
-        }
-    }
+pub fn rook_moves(square: usize, empty: BitBoard) -> BitBoard {
+    rook_attacks(square, !empty)
+}
 
-    attacks
+//Attacks with the first blocker on each ray taken off the board
+#[inline]
+pub fn rook_xray(square: usize, empty: BitBoard) -> BitBoard {
+    let blockers = rook_moves(square, empty) & !empty;
+    rook_moves(square, empty | blockers)
 }
 
 #[inline]
-pub fn queen_xray(square: usize, empty: BitBoard) -> BitBoard {
-    rook_xray(square, empty) | bishop_xray(square, empty)
+pub fn bishop_xray(square: usize, empty: BitBoard) -> BitBoard {
+    let blockers = bishop_moves(square, empty) & !empty;
+    bishop_moves(square, empty | blockers)
 }
 
 #[inline]
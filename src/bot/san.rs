@@ -0,0 +1,217 @@
+use std::fmt;
+
+use super::{Board, Move, MoveList, Piece, Square};
+
+/// Why a SAN string could not be turned into a move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SanError {
+    /// The string is not written in SAN at all.
+    Syntax,
+    /// No legal move in the position matches.
+    Illegal,
+    /// More than one legal move matches, the disambiguation is missing.
+    Ambiguous,
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanError::Syntax => write!(f, "invalid SAN"),
+            SanError::Illegal => write!(f, "no legal move matches the SAN"),
+            SanError::Ambiguous => write!(f, "SAN matches more than one legal move"),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+fn piece_letter(piece: Piece) -> char {
+    match piece {
+        Piece::Knight => 'N',
+        Piece::Bishop => 'B',
+        Piece::Rook => 'R',
+        Piece::Queen => 'Q',
+        Piece::King => 'K',
+        _ => 'P',
+    }
+}
+
+fn letter_piece(letter: char) -> Option<Piece> {
+    match letter {
+        'N' => Some(Piece::Knight),
+        'B' => Some(Piece::Bishop),
+        'R' => Some(Piece::Rook),
+        'Q' => Some(Piece::Queen),
+        'K' => Some(Piece::King),
+        _ => None,
+    }
+}
+
+//Everything a SAN string says about the move it stands for
+struct SanMove {
+    piece: Piece,
+    from_file: Option<usize>,
+    from_rank: Option<usize>,
+    to: Square,
+    promotion: Piece,
+}
+
+fn parse_san_move(san: &str) -> Option<SanMove> {
+    let mut chars: Vec<char> = san.chars().collect();
+    let mut promotion = Piece::None;
+    if let Some(piece) = chars.last().copied().and_then(letter_piece) {
+        if piece == Piece::King {
+            return None;
+        }
+        promotion = piece;
+        chars.pop();
+        if chars.last() == Some(&'=') {
+            chars.pop();
+        }
+    }
+    if chars.len() < 2 {
+        return None;
+    }
+    let to: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let to: Square = to.parse().ok()?;
+
+    let mut rest = chars.as_slice();
+    let piece = match rest.first().copied().and_then(letter_piece) {
+        Some(piece) => {
+            rest = &rest[1..];
+            piece
+        }
+        None => Piece::Pawn,
+    };
+    if rest.last() == Some(&'x') {
+        rest = &rest[..rest.len() - 1];
+    }
+    let mut from_file = None;
+    let mut from_rank = None;
+    for &c in rest {
+        match c {
+            'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+                from_file = Some(c as usize - 'a' as usize)
+            }
+            '1'..='8' if from_rank.is_none() => from_rank = Some(c as usize - '1' as usize),
+            _ => return None,
+        }
+    }
+    if promotion != Piece::None && piece != Piece::Pawn {
+        return None;
+    }
+    Some(SanMove {
+        piece,
+        from_file,
+        from_rank,
+        to,
+        promotion,
+    })
+}
+
+impl Board {
+    /// Standard algebraic notation for a legal move in this position, with
+    /// `+` or `#` appended when it gives check or mate.
+    pub fn to_san(&self, board_move: Move) -> String {
+        let mut res = if board_move.is_castle() {
+            if board_move.to().file() > board_move.from().file() {
+                "O-O".to_string()
+            } else {
+                "O-O-O".to_string()
+            }
+        } else {
+            self.san_without_suffix(board_move)
+        };
+
+        let mut after = self.clone();
+        after.make_move(board_move);
+        if after.is_check() {
+            let mut replies = MoveList::new();
+            after.legal_moves(&mut replies);
+            res.push(if replies.is_empty() { '#' } else { '+' });
+        }
+        res
+    }
+
+    fn san_without_suffix(&self, board_move: Move) -> String {
+        let from = board_move.from();
+        let to = board_move.to();
+        let piece = self.piece_at(from.index());
+        let mut res = String::new();
+        if piece == Piece::Pawn {
+            if board_move.is_capture() {
+                res.push((b'a' + from.file() as u8) as char);
+                res.push('x');
+            }
+            res.push_str(&to.to_string());
+            if board_move.is_promotion() {
+                res.push('=');
+                res.push(piece_letter(board_move.promotion()));
+            }
+            return res;
+        }
+
+        res.push(piece_letter(piece));
+        //Other pieces of the same kind that could also go to `to`
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        let rivals: Vec<Square> = moves
+            .iter()
+            .filter(|m| !m.is_castle() && m.to() == to && m.from() != from)
+            .filter(|m| self.piece_at(m.from().index()) == piece)
+            .map(|m| m.from())
+            .collect();
+        if !rivals.is_empty() {
+            let file = (b'a' + from.file() as u8) as char;
+            let rank = (b'1' + from.rank() as u8) as char;
+            if rivals.iter().all(|rival| rival.file() != from.file()) {
+                res.push(file);
+            } else if rivals.iter().all(|rival| rival.rank() != from.rank()) {
+                res.push(rank);
+            } else {
+                res.push(file);
+                res.push(rank);
+            }
+        }
+        if board_move.is_capture() {
+            res.push('x');
+        }
+        res.push_str(&to.to_string());
+        res
+    }
+
+    /// Finds the legal move written in SAN. Check and mate markers and
+    /// trailing annotations such as `!?` are ignored.
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+
+        let castle = match san {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some(kingside) = castle {
+            return moves
+                .iter()
+                .find(|m| m.is_castle() && (m.to().file() > m.from().file()) == kingside)
+                .ok_or(SanError::Illegal);
+        }
+
+        let parsed = parse_san_move(san).ok_or(SanError::Syntax)?;
+        let mut matching = moves.iter().filter(|m| {
+            !m.is_castle()
+                && m.to() == parsed.to
+                && m.promotion() == parsed.promotion
+                && self.piece_at(m.from().index()) == parsed.piece
+                && parsed.from_file.is_none_or(|file| m.from().file() == file)
+                && parsed.from_rank.is_none_or(|rank| m.from().rank() == rank)
+        });
+        match (matching.next(), matching.next()) {
+            (Some(board_move), None) => Ok(board_move),
+            (Some(_), Some(_)) => Err(SanError::Ambiguous),
+            (None, _) => Err(SanError::Illegal),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::pawns::PawnTable;
+use super::transposition::Bound;
+use super::{Board, Move, MoveList, TranspositionTable};
+
+pub const MATE: i32 = 100_000;
+pub const MAX_PLY: usize = 128;
+const INFINITY: i32 = MATE + 1;
+
+//How often the clock and stop flag are polled, in nodes
+const CHECK_INTERVAL: u64 = 1024;
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchLimits {
+    pub depth: Option<usize>,
+    pub movetime: Option<Duration>,
+    pub nodes: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    //Centipawns from the side to move's point of view
+    pub score: i32,
+    pub depth: usize,
+    pub nodes: u64,
+    pub time: Duration,
+    pub pv: Vec<Move>,
+}
+
+impl SearchResult {
+    /// Moves until mate when the score is a mate score, negative if the side
+    /// to move is getting mated.
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE - MAX_PLY as i32 {
+            return None;
+        }
+        let plies = MATE - self.score.abs();
+        let moves = (plies + 1) / 2;
+        Some(if self.score > 0 { moves } else { -moves })
+    }
+}
+
+struct Searcher<'a> {
+    stop: &'a AtomicBool,
+    start: Instant,
+    deadline: Option<Instant>,
+    node_limit: Option<u64>,
+    nodes: u64,
+    aborted: bool,
+    pawns: PawnTable,
+    table: &'a mut TranspositionTable,
+}
+
+impl Searcher<'_> {
+    fn should_stop(&mut self) -> bool {
+        if self.aborted {
+            return true;
+        }
+        if let Some(limit) = self.node_limit {
+            if self.nodes >= limit {
+                self.aborted = true;
+            }
+        }
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            if self.stop.load(Ordering::Relaxed) {
+                self.aborted = true;
+            }
+            if let Some(deadline) = self.deadline {
+                if Instant::now() >= deadline {
+                    self.aborted = true;
+                }
+            }
+        }
+        self.aborted
+    }
+
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        pv.clear();
+        //A position that has been seen before can be repeated again, so a
+        //second occurrence is scored as the draw it can be forced into
+        if board.is_repetition(2) {
+            return 0;
+        }
+        if depth == 0 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+        self.nodes += 1;
+        if self.should_stop() || board.is_insufficient_material() {
+            return 0;
+        }
+        let mut moves = MoveList::new();
+        board.legal_moves(&mut moves);
+        if moves.is_empty() {
+            //Prefer the shortest mate
+            return if board.is_check() {
+                -MATE + ply as i32
+            } else {
+                0
+            };
+        }
+        if board.is_fifty_move_draw() {
+            return 0;
+        }
+        if ply >= MAX_PLY {
+            return board.evaluate_cached(&mut self.pawns);
+        }
+
+        let key = board.hash();
+        let mut hash_move = Move::NULL;
+        if let Some(entry) = self.table.probe(key) {
+            hash_move = entry.best_move;
+            if entry.depth as usize >= depth {
+                let score = score_from_table(entry.score, ply);
+                let cutoff = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                    Bound::None | Bound::Perft => false,
+                };
+                if cutoff {
+                    return score;
+                }
+            }
+        }
+
+        //The best move found at this position before goes first
+        for (board_move, order) in moves.as_mut_slice() {
+            if *board_move == hash_move {
+                *order = usize::MAX;
+            }
+        }
+        moves.as_mut_slice().sort_by_key(|m| Reverse(m.1));
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = Move::NULL;
+        let mut child_pv = Vec::new();
+        for board_move in moves.iter() {
+            board.make_move(board_move);
+            let score = -self.negamax(board, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            board.undo_move(board_move);
+            if self.aborted {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                best_move = board_move;
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(board_move);
+                    pv.append(&mut child_pv);
+                }
+                if score >= beta {
+                    break;
+                }
+            }
+        }
+        let bound = if best <= original_alpha {
+            //Every move failed low, so none of them is known to be best
+            best_move = Move::NULL;
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        let score = score_to_table(best, ply);
+        self.table.store(key, depth as u8, bound, score, best_move);
+        best
+    }
+
+    //Resolves captures and promotions before trusting the static evaluation.
+    //The side to move can stand pat unless it is in check, in which case every
+    //evasion is searched.
+    fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.should_stop() || board.is_insufficient_material() {
+            return 0;
+        }
+        let mut moves = MoveList::new();
+        board.legal_moves(&mut moves);
+        let in_check = board.is_check();
+        if moves.is_empty() {
+            return if in_check { -MATE + ply as i32 } else { 0 };
+        }
+        if ply >= MAX_PLY {
+            return board.evaluate_cached(&mut self.pawns);
+        }
+
+        let mut best = -INFINITY;
+        if !in_check {
+            best = board.evaluate_cached(&mut self.pawns);
+            if best >= beta {
+                return best;
+            }
+            alpha = alpha.max(best);
+        }
+        moves.as_mut_slice().sort_by_key(|m| Reverse(m.1));
+        for board_move in moves.iter() {
+            if !in_check && !board_move.is_capture() && !board_move.is_promotion() {
+                continue;
+            }
+            board.make_move(board_move);
+            let score = -self.quiescence(board, ply + 1, -beta, -alpha);
+            board.undo_move(board_move);
+            if self.aborted {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                alpha = alpha.max(score);
+                if score >= beta {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn search_root(
+        &mut self,
+        board: &mut Board,
+        moves: &MoveList,
+        depth: usize,
+    ) -> Option<(i32, Vec<Move>)> {
+        self.nodes += 1;
+        let mut alpha = -INFINITY;
+        let mut pv = Vec::new();
+        let mut child_pv = Vec::new();
+        for board_move in moves.iter() {
+            board.make_move(board_move);
+            let score = -self.negamax(board, depth - 1, 1, -INFINITY, -alpha, &mut child_pv);
+            board.undo_move(board_move);
+            if self.aborted {
+                return None;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(board_move);
+                pv.append(&mut child_pv);
+            }
+        }
+        self.table
+            .store(board.hash(), depth as u8, Bound::Exact, alpha, pv[0]);
+        Some((alpha, pv))
+    }
+}
+
+//Mate scores are stored relative to the position rather than the root, so
+//they stay right when the position is reached at another ply
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score > MATE - MAX_PLY as i32 {
+        score + ply as i32
+    } else if score < -MATE + MAX_PLY as i32 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score > MATE - MAX_PLY as i32 {
+        score - ply as i32
+    } else if score < -MATE + MAX_PLY as i32 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+impl Board {
+    /// Iterative deepening alpha-beta search from the current position.
+    /// `info` is called after every completed iteration; the last completed
+    /// iteration is returned once a limit is hit or `stop` is set. Entries in
+    /// `table` carry over to later searches.
+    pub fn search(
+        &mut self,
+        limits: &SearchLimits,
+        table: &mut TranspositionTable,
+        stop: &AtomicBool,
+        mut info: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        let start = Instant::now();
+        let mut searcher = Searcher {
+            stop,
+            start,
+            deadline: limits.movetime.map(|time| start + time),
+            node_limit: limits.nodes,
+            nodes: 0,
+            aborted: false,
+            pawns: PawnTable::new(),
+            table,
+        };
+        searcher.table.new_search();
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            depth: 0,
+            nodes: 0,
+            time: Duration::ZERO,
+            pv: Vec::new(),
+        };
+
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        moves.as_mut_slice().sort_by_key(|m| Reverse(m.1));
+        if moves.is_empty() {
+            result.score = if self.is_check() { -MATE } else { 0 };
+            return result;
+        }
+        //Always have something to play even if the first iteration is cut short
+        result.best_move = Some(moves[0].0);
+
+        let max_depth = limits.depth.unwrap_or(MAX_PLY).clamp(1, MAX_PLY);
+        for depth in 1..=max_depth {
+            let (score, pv) = match searcher.search_root(self, &moves, depth) {
+                Some(iteration) => iteration,
+                None => break,
+            };
+            result.best_move = pv.first().cloned();
+            result.score = score;
+            result.depth = depth;
+            result.nodes = searcher.nodes;
+            result.time = searcher.start.elapsed();
+            result.pv = pv;
+            info(&result);
+
+            //Search the best move first on the next iteration
+            if let Some(best) = result.best_move {
+                let position = moves.iter().position(|m| m == best);
+                if let Some(i) = position {
+                    moves.as_mut_slice()[..=i].rotate_right(1);
+                }
+            }
+            if result.mate_in().is_some() {
+                break;
+            }
+        }
+        result.nodes = searcher.nodes;
+        result.time = searcher.start.elapsed();
+        result
+    }
+}
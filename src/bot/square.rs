@@ -0,0 +1,87 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A square of the board, numbered a1 = 0, b1 = 1 up to h8 = 63.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    pub const fn new(file: usize, rank: usize) -> Option<Square> {
+        if file < 8 && rank < 8 {
+            Some(Square((rank * 8 + file) as u8))
+        } else {
+            None
+        }
+    }
+
+    pub const fn from_index(index: usize) -> Option<Square> {
+        if index < 64 {
+            Some(Square(index as u8))
+        } else {
+            None
+        }
+    }
+
+    //For squares that come out of a bitboard or a packed move
+    #[inline]
+    pub(crate) const fn from_index_unchecked(index: usize) -> Square {
+        Square(index as u8)
+    }
+
+    #[inline]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// 0 for the a-file up to 7 for the h-file.
+    #[inline]
+    pub const fn file(self) -> usize {
+        self.0 as usize % 8
+    }
+
+    /// 0 for the first rank up to 7 for the eighth.
+    #[inline]
+    pub const fn rank(self) -> usize {
+        self.0 as usize / 8
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = (b'a' + self.file() as u8) as char;
+        let rank = (b'1' + self.rank() as u8) as char;
+        write!(f, "{}{}", file, rank)
+    }
+}
+
+/// The string was not a square name such as `e4`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseSquareError;
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid square")
+    }
+}
+
+impl std::error::Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Square, ParseSquareError> {
+        let mut chars = s.chars();
+        let file = match chars.next() {
+            Some(f @ 'a'..='h') => f as usize - 'a' as usize,
+            _ => return Err(ParseSquareError),
+        };
+        let rank = match chars.next() {
+            Some(r @ '1'..='8') => r as usize - '1' as usize,
+            _ => return Err(ParseSquareError),
+        };
+        if chars.next().is_some() {
+            return Err(ParseSquareError);
+        }
+        Ok(Square::from_index_unchecked(rank * 8 + file))
+    }
+}
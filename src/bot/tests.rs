@@ -0,0 +1,153 @@
+use super::*;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+
+fn find_move(board: &Board, uci: &str) -> Move {
+    board.parse_uci_move(uci).unwrap()
+}
+
+#[test]
+fn castling_moves_king_and_rook() {
+    let white = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let black = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1";
+    let cases = [
+        (white, "e1g1", "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1"),
+        (white, "e1c1", "r3k2r/8/8/8/8/8/8/2KR3R b kq - 1 1"),
+        (black, "e8g8", "r4rk1/8/8/8/8/8/8/R3K2R w KQ - 1 2"),
+        (black, "e8c8", "2kr3r/8/8/8/8/8/8/R3K2R w KQ - 1 2"),
+    ];
+    for (fen, uci, expected) in cases {
+        let mut board = Board::from_fen(fen).unwrap();
+        let castle = find_move(&board, uci);
+        board.make_move(castle);
+        assert_eq!(board.to_fen(), expected);
+        assert_eq!(board.occupied, board.white_occupied | board.black_occupied);
+        board.undo_move(castle);
+        assert_eq!(board.to_fen(), fen);
+    }
+}
+
+//Walks the whole tree checking the incremental key at every node
+fn assert_hash_consistent(board: &mut Board, depth: usize) {
+    assert_eq!(board.hash(), board.compute_hash(), "{}", board.to_fen());
+    assert_eq!(
+        board.pawn_hash(),
+        board.compute_pawn_hash(),
+        "{}",
+        board.to_fen()
+    );
+    if depth == 0 {
+        return;
+    }
+    let mut moves = MoveList::new();
+    board.legal_moves(&mut moves);
+    for board_move in moves.iter() {
+        let hash = board.hash();
+        let pawn_hash = board.pawn_hash();
+        board.make_move(board_move);
+        assert_hash_consistent(board, depth - 1);
+        board.undo_move(board_move);
+        assert_eq!(board.hash(), hash);
+        assert_eq!(board.pawn_hash(), pawn_hash);
+    }
+}
+
+#[test]
+fn incremental_hash_matches_recomputation() {
+    for fen in [START_FEN, KIWIPETE, POSITION_3, POSITION_4, POSITION_5] {
+        let mut board = Board::from_fen(fen).unwrap();
+        assert_hash_consistent(&mut board, 3);
+    }
+    let mut board = Board::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1").unwrap();
+    assert_hash_consistent(&mut board, 3);
+}
+
+fn assert_cached_eval_matches(board: &mut Board, pawns: &mut pawns::PawnTable, depth: usize) {
+    assert_eq!(
+        board.evaluate_cached(pawns),
+        board.evaluate(),
+        "{}",
+        board.to_fen()
+    );
+    if depth == 0 {
+        return;
+    }
+    let mut moves = MoveList::new();
+    board.legal_moves(&mut moves);
+    for board_move in moves.iter() {
+        board.make_move(board_move);
+        assert_cached_eval_matches(board, pawns, depth - 1);
+        board.undo_move(board_move);
+    }
+}
+
+#[test]
+fn pawn_table_matches_fresh_evaluation() {
+    let mut pawns = pawns::PawnTable::new();
+    for fen in [START_FEN, KIWIPETE, POSITION_3, POSITION_4, POSITION_5] {
+        let mut board = Board::from_fen(fen).unwrap();
+        assert_cached_eval_matches(&mut board, &mut pawns, 3);
+    }
+}
+
+#[test]
+fn magic_lookups_match_ray_scans() {
+    use super::magic::{bishop_attacks, rook_attacks};
+    use super::pseudomoves::{ray_bishop_moves, ray_rook_moves};
+    //xorshift, sparse and dense boards both matter so mix a few draws
+    let mut state: u64 = 0x9E3779B97F4A7C15;
+    let mut random = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..2000 {
+        let occupied = match random() % 3 {
+            0 => random() & random() & random(),
+            1 => random() & random(),
+            _ => random(),
+        };
+        for square in 0..64 {
+            assert_eq!(
+                rook_attacks(square, occupied),
+                ray_rook_moves(square, !occupied)
+            );
+            assert_eq!(
+                bishop_attacks(square, occupied),
+                ray_bishop_moves(square, !occupied)
+            );
+        }
+    }
+}
+
+#[test]
+fn perft_and_search_entries_share_a_table() {
+    use super::transposition::Bound;
+    use std::sync::atomic::AtomicBool;
+    let mut table = TranspositionTable::new(1);
+    let mut board = Board::new();
+    let limits = SearchLimits {
+        depth: Some(3),
+        ..Default::default()
+    };
+    let result = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+    let entry = *table.probe(board.hash()).unwrap();
+    assert_eq!(entry.bound, Bound::Exact);
+    assert_eq!(Some(entry.best_move), result.best_move);
+    assert_eq!(table.probe_perft(board.hash(), 3), None);
+    assert_eq!(board.perft_hashed(3, &mut table), 8902);
+
+    //The perft count replaced the search entry and isn't mistaken for one
+    assert_eq!(table.probe_perft(board.hash(), 3), Some(8902));
+    assert!(table.probe(board.hash()).is_none());
+    let result = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+    assert_eq!(
+        table.probe(board.hash()).unwrap().best_move,
+        result.best_move.unwrap()
+    );
+    assert_eq!(board.perft_hashed(3, &mut table), 8902);
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use super::eval::{taper, Score, MAX_PHASE};
+use super::Board;
+
+/// One evaluation term for both sides, before tapering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceTerm {
+    pub name: &'static str,
+    pub white: Score,
+    pub black: Score,
+}
+
+/// Every term of the static evaluation, to see why a position is scored the
+/// way it is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalTrace {
+    pub terms: Vec<TraceTerm>,
+    pub phase: i32,
+}
+
+impl EvalTrace {
+    /// Sum of the terms, white minus black.
+    pub fn total(&self) -> Score {
+        self.terms.iter().fold(Score::default(), |total, term| {
+            total + term.white - term.black
+        })
+    }
+
+    /// Tapered evaluation in centipawns from white's point of view.
+    pub fn score(&self) -> i32 {
+        taper(self.total(), self.phase)
+    }
+}
+
+impl Board {
+    /// Breaks the evaluation down into its terms. `evaluate` gives the same
+    /// score, negated when black is to move.
+    pub fn eval_trace(&self) -> EvalTrace {
+        let pawns = self.pawn_entry();
+        let king = [self.king_safety(true), self.king_safety(false)];
+        let mut terms = Vec::new();
+        let mut push = |name, white, black| terms.push(TraceTerm { name, white, black });
+        push("Material", self.material(true), self.material(false));
+        push(
+            "Piece squares",
+            self.piece_squares(true),
+            self.piece_squares(false),
+        );
+        push("Mobility", self.mobility(true), self.mobility(false));
+        push("Doubled pawns", pawns.white.doubled, pawns.black.doubled);
+        push("Isolated pawns", pawns.white.isolated, pawns.black.isolated);
+        push("Backward pawns", pawns.white.backward, pawns.black.backward);
+        push(
+            "Connected pawns",
+            pawns.white.connected,
+            pawns.black.connected,
+        );
+        push("Passed pawns", pawns.white.passed, pawns.black.passed);
+        push(
+            "Passer kings",
+            self.passed_pawn_kings(true, pawns.white_passed),
+            self.passed_pawn_kings(false, pawns.black_passed),
+        );
+        push(
+            "Candidate pawns",
+            pawns.white.candidate,
+            pawns.black.candidate,
+        );
+        push("King attacks", king[0].attacks, king[1].attacks);
+        push("Pawn shield", king[0].shield, king[1].shield);
+        push("Pawn storm", king[0].storm, king[1].storm);
+        push("Open king files", king[0].open_files, king[1].open_files);
+        EvalTrace {
+            terms,
+            phase: self.phase(),
+        }
+    }
+}
+
+const RULE: &str = "-----------------+---------------+---------------+--------------";
+
+impl fmt::Display for EvalTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = format!(
+            "{:<16} | {:^13} | {:^13} | {:^13}",
+            "Term", "White", "Black", "Total"
+        );
+        writeln!(f, "{}", header.trim_end())?;
+        writeln!(
+            f,
+            "{:<16} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6}",
+            "", "MG", "EG", "MG", "EG", "MG", "EG"
+        )?;
+        writeln!(f, "{}", RULE)?;
+        let row = |f: &mut fmt::Formatter<'_>, name: &str, white: Score, black: Score| {
+            let total = white - black;
+            writeln!(
+                f,
+                "{:<16} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6}",
+                name, white.mg, white.eg, black.mg, black.eg, total.mg, total.eg
+            )
+        };
+        let mut white = Score::default();
+        let mut black = Score::default();
+        for term in &self.terms {
+            row(f, term.name, term.white, term.black)?;
+            white += term.white;
+            black += term.black;
+        }
+        writeln!(f, "{}", RULE)?;
+        row(f, "Total", white, black)?;
+        writeln!(f)?;
+        writeln!(f, "Phase: {}/{}", self.phase, MAX_PHASE)?;
+        write!(
+            f,
+            "Evaluation: {:+} cp, white's point of view",
+            self.score()
+        )
+    }
+}
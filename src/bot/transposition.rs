@@ -0,0 +1,187 @@
+use std::mem::size_of;
+
+use super::{Board, Move, MoveList};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Bound {
+    //Empty slot
+    #[default]
+    None,
+    Exact,
+    //Failed high, the score is at least this much
+    Lower,
+    //Failed low, the score is at most this much
+    Upper,
+    //Subtree node count from perft, which has no score
+    Perft,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Entry {
+    key: u64,
+    pub nodes: u64,
+    pub score: i32,
+    pub best_move: Move,
+    pub depth: u8,
+    pub bound: Bound,
+    age: u8,
+}
+
+/// Fixed size hash table indexed by the low bits of the position key.
+pub struct TranspositionTable {
+    entries: Vec<Entry>,
+    mask: usize,
+    age: u8,
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> Self {
+        let mut table = TranspositionTable {
+            entries: Vec::new(),
+            mask: 0,
+            age: 0,
+        };
+        table.resize(megabytes);
+        table
+    }
+
+    //Rounds down to a power of two number of entries so indexing is a mask
+    pub fn resize(&mut self, megabytes: usize) {
+        let wanted = (megabytes * 1024 * 1024 / size_of::<Entry>()).max(1);
+        let len = 1 << (usize::BITS - 1 - wanted.leading_zeros());
+        self.entries = vec![Entry::default(); len];
+        self.mask = len - 1;
+        self.age = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(Entry::default());
+        self.age = 0;
+    }
+
+    //Call once per search so entries from earlier searches get replaced first
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    fn lookup(&self, key: u64, bound_wanted: impl Fn(Bound) -> bool) -> Option<&Entry> {
+        let entry = &self.entries[key as usize & self.mask];
+        if bound_wanted(entry.bound) && entry.key == key {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    //Search results only, perft counts sharing the table are skipped
+    #[inline]
+    pub(super) fn probe(&self, key: u64) -> Option<&Entry> {
+        self.lookup(key, |bound| !matches!(bound, Bound::None | Bound::Perft))
+    }
+
+    #[inline]
+    pub(super) fn store(&mut self, key: u64, depth: u8, bound: Bound, score: i32, best_move: Move) {
+        let age = self.age;
+        let entry = &mut self.entries[key as usize & self.mask];
+        //Keep deeper results from the current search over shallower ones
+        if entry.key != key && entry.age == age && entry.depth > depth {
+            return;
+        }
+        //Don't lose the best move when re-storing a position without one
+        let best_move = if best_move.is_null() && entry.key == key {
+            entry.best_move
+        } else {
+            best_move
+        };
+        *entry = Entry {
+            key,
+            nodes: 0,
+            score,
+            best_move,
+            depth,
+            bound,
+            age,
+        };
+    }
+
+    #[inline]
+    pub(super) fn probe_perft(&self, key: u64, depth: u8) -> Option<u64> {
+        self.lookup(key, |bound| bound == Bound::Perft)
+            .filter(|entry| entry.depth == depth)
+            .map(|entry| entry.nodes)
+    }
+
+    #[inline]
+    pub(super) fn store_perft(&mut self, key: u64, depth: u8, nodes: u64) {
+        let entry = &mut self.entries[key as usize & self.mask];
+        if entry.key != key && entry.depth > depth {
+            return;
+        }
+        *entry = Entry {
+            key,
+            nodes,
+            score: 0,
+            best_move: Move::NULL,
+            depth,
+            bound: Bound::Perft,
+            age: self.age,
+        };
+    }
+}
+
+impl Board {
+    /// Perft that caches subtree counts in `table`.
+    pub fn perft_hashed(&mut self, depth: usize, table: &mut TranspositionTable) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+        if depth > 1 {
+            if let Some(nodes) = table.probe_perft(self.hash, depth as u8) {
+                return nodes as usize;
+            }
+        }
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        if depth == 1 {
+            return moves.len();
+        }
+        let mut total = 0;
+        for board_move in moves.iter() {
+            self.make_move(board_move);
+            total += self.perft_hashed(depth - 1, table);
+            self.undo_move(board_move);
+        }
+        table.store_perft(self.hash, depth as u8, total as u64);
+        total
+    }
+
+    //Perft split up by root move, for finding which line a generation bug is in
+    pub fn perft_divide(
+        &mut self,
+        depth: usize,
+        table: &mut TranspositionTable,
+    ) -> Vec<(Move, usize)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        let mut moves = MoveList::new();
+        self.legal_moves(&mut moves);
+        let mut res = Vec::new();
+        for board_move in moves.iter() {
+            self.make_move(board_move);
+            let nodes = self.perft_hashed(depth - 1, table);
+            self.undo_move(board_move);
+            res.push((board_move, nodes));
+        }
+        res
+    }
+}
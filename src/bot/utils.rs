@@ -41,15 +41,26 @@ pub const fn south_west_one(set: u64) -> u64 {
     (set >> 9) & NOT_H_FILE
 }
 
-pub fn print_bit_board(board: u64) {
-    let mut bytes = board.to_ne_bytes();
-    bytes.reverse();
-    for i in bytes {
-        let s = format!("{:#010b}", i);
-        let s: String = s[2..s.len()].chars().rev().collect();
-        println!("{}", s.replace("0", "."));
-    }
-    println!();
+//Smears every bit up or down its file
+#[inline]
+pub const fn north_fill(mut set: u64) -> u64 {
+    set |= set << 8;
+    set |= set << 16;
+    set |= set << 32;
+    set
+}
+
+#[inline]
+pub const fn south_fill(mut set: u64) -> u64 {
+    set |= set >> 8;
+    set |= set >> 16;
+    set |= set >> 32;
+    set
+}
+
+#[inline]
+pub const fn file_fill(set: u64) -> u64 {
+    north_fill(set) | south_fill(set)
 }
 
 pub struct BitBoardIter(pub u64);
@@ -66,32 +77,3 @@ impl Iterator for BitBoardIter {
         Some(res)
     }
 }
-
-fn string_square(square: usize) -> String {
-    let file = square % 8;
-    let rank = square / 8;
-    let mut res = String::new();
-    match file {
-        0 => res.push('a'),
-        1 => res.push('b'),
-        2 => res.push('c'),
-        3 => res.push('d'),
-        4 => res.push('e'),
-        5 => res.push('f'),
-        6 => res.push('g'),
-        7 => res.push('h'),
-        _ => panic!("uhhh"),
-    }
-    match rank {
-        0 => res.push('1'),
-        1 => res.push('2'),
-        2 => res.push('3'),
-        3 => res.push('4'),
-        4 => res.push('5'),
-        5 => res.push('6'),
-        6 => res.push('7'),
-        7 => res.push('8'),
-        _ => panic!("uhhh"),
-    }
-    res
-}
@@ -0,0 +1,167 @@
+use super::pseudomoves::PAWN_ATTACKS;
+use super::utils::*;
+use super::{
+    BitBoard, Board, Piece, BLACK_KINGSIDE, BLACK_QUEENSIDE, WHITE_KINGSIDE, WHITE_QUEENSIDE,
+};
+
+struct Keys {
+    //Indexed by colour (white first), piece and square
+    pieces: [[[u64; 64]; 6]; 2],
+    //Indexed by the four castling rights as bits
+    castling: [u64; 16],
+    en_passant_file: [u64; 8],
+    black_to_play: u64,
+}
+
+//splitmix64, fixed seed so keys are the same every build
+const fn next(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+static KEYS: Keys = {
+    let mut state: u64 = 0x2545f4914f6cdd1d;
+    let mut pieces = [[[0; 64]; 6]; 2];
+    let mut color = 0;
+    while color < 2 {
+        let mut piece = 0;
+        while piece < 6 {
+            let mut square = 0;
+            while square < 64 {
+                pieces[color][piece][square] = next(&mut state);
+                square += 1;
+            }
+            piece += 1;
+        }
+        color += 1;
+    }
+    let mut castling = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        castling[i] = next(&mut state);
+        i += 1;
+    }
+    let mut en_passant_file = [0; 8];
+    let mut i = 0;
+    while i < 8 {
+        en_passant_file[i] = next(&mut state);
+        i += 1;
+    }
+    Keys {
+        pieces,
+        castling,
+        en_passant_file,
+        black_to_play: next(&mut state),
+    }
+};
+
+#[inline]
+fn piece_index(piece: Piece) -> usize {
+    match piece {
+        Piece::Pawn => 0,
+        Piece::Knight => 1,
+        Piece::Bishop => 2,
+        Piece::Rook => 3,
+        Piece::Queen => 4,
+        Piece::King => 5,
+        Piece::None => panic!(),
+    }
+}
+
+#[inline]
+pub fn piece_key(white: bool, piece: Piece, square: usize) -> u64 {
+    KEYS.pieces[!white as usize][piece_index(piece)][square]
+}
+
+#[inline]
+pub fn castle_key(castle: BitBoard) -> u64 {
+    let mut rights = 0;
+    for (i, right) in [
+        WHITE_KINGSIDE,
+        WHITE_QUEENSIDE,
+        BLACK_KINGSIDE,
+        BLACK_QUEENSIDE,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if castle & right == right {
+            rights |= 1 << i;
+        }
+    }
+    KEYS.castling[rights]
+}
+
+#[inline]
+pub fn side_key() -> u64 {
+    KEYS.black_to_play
+}
+
+impl Board {
+    //The en passant square only changes the position when a pawn of the side
+    //to move can take on it, otherwise repetitions after a double push are missed
+    #[inline]
+    pub(super) fn en_passant_key(&self) -> u64 {
+        if self.en_passant == 0 {
+            return 0;
+        }
+        let square = self.en_passant.trailing_zeros() as usize;
+        let white = self.white_to_play;
+        if PAWN_ATTACKS[white as usize][square] & self.pieces(white, Piece::Pawn) == 0 {
+            return 0;
+        }
+        KEYS.en_passant_file[square % 8]
+    }
+
+    /// Zobrist key of the position, kept up to date by make_move and undo_move.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Key of the pawn placement alone, shared by every position with the
+    /// same pawns.
+    pub fn pawn_hash(&self) -> u64 {
+        self.pawn_hash
+    }
+
+    pub(super) fn compute_pawn_hash(&self) -> u64 {
+        let mut key = 0;
+        for square in BitBoardIter(self.white_pawns) {
+            key ^= piece_key(true, Piece::Pawn, square);
+        }
+        for square in BitBoardIter(self.black_pawns) {
+            key ^= piece_key(false, Piece::Pawn, square);
+        }
+        key
+    }
+
+    //Builds the key from scratch
+    pub(super) fn compute_hash(&self) -> u64 {
+        let pieces = [
+            (Piece::Pawn, self.white_pawns, self.black_pawns),
+            (Piece::Knight, self.white_knights, self.black_knights),
+            (Piece::Bishop, self.white_bishops, self.black_bishops),
+            (Piece::Rook, self.white_rooks, self.black_rooks),
+            (Piece::Queen, self.white_queens, self.black_queens),
+            (Piece::King, self.white_kings, self.black_kings),
+        ];
+        let mut key = 0;
+        for (piece, white, black) in pieces {
+            for square in BitBoardIter(white) {
+                key ^= piece_key(true, piece, square);
+            }
+            for square in BitBoardIter(black) {
+                key ^= piece_key(false, piece, square);
+            }
+        }
+        key ^= castle_key(self.castle);
+        key ^= self.en_passant_key();
+        if !self.white_to_play {
+            key ^= side_key();
+        }
+        key
+    }
+}
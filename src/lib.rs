@@ -0,0 +1,9 @@
+//! Bitboard chess move generation, perft and search.
+
+mod bot;
+
+pub use bot::{
+    Board, Color, EvalTrace, FenError, Game, GameMove, Move, MoveList, Outcome, ParseSquareError,
+    PgnError, PgnErrorKind, PgnReader, Piece, SanError, Score, SearchLimits, SearchResult, Square,
+    TraceTerm, TranspositionTable, UciMoveError, START_FEN,
+};
@@ -1,11 +1,32 @@
-#![allow(dead_code)]
+mod bench;
+mod uci;
 
-mod bot;
+use std::{env, io, process};
 
-use bot::Board;
+use chessmate::{Board, START_FEN};
 
 fn main() {
-    let mut board = Board::default();
-    println!("{}", board.perft(5));
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("bench") => bench::run(args.get(1).and_then(|depth| depth.parse().ok())),
+        Some("eval") => eval(&args[1..]),
+        _ => uci::run(io::stdin().lock(), io::stdout()),
+    }
 }
 
+//Prints the evaluation breakdown of the FEN given as the remaining
+//arguments, or of the start position
+fn eval(fen: &[String]) {
+    let fen = if fen.is_empty() {
+        START_FEN.to_string()
+    } else {
+        fen.join(" ")
+    };
+    match Board::from_fen(&fen) {
+        Ok(board) => println!("{}", board.eval_trace()),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
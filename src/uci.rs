@@ -0,0 +1,436 @@
+use std::any::Any;
+use std::io::{BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chessmate::{Board, Move, MoveList, SearchLimits, SearchResult, TranspositionTable, START_FEN};
+
+const NAME: &str = "Chessmate";
+const AUTHOR: &str = "Dhhoyt";
+
+//Moves assumed to be left in the game when the GUI doesn't send movestogo
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+
+const DEFAULT_HASH: usize = 16;
+const MAX_HASH: usize = 4096;
+
+struct Options {
+    move_overhead: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { move_overhead: 10 }
+    }
+}
+
+#[derive(Default)]
+struct GoParams {
+    depth: Option<usize>,
+    movetime: Option<u64>,
+    wtime: Option<u64>,
+    btime: Option<u64>,
+    winc: Option<u64>,
+    binc: Option<u64>,
+    movestogo: Option<u64>,
+    nodes: Option<u64>,
+    infinite: bool,
+    perft: Option<usize>,
+}
+
+struct Uci<W: Write + Send + 'static> {
+    out: Arc<Mutex<W>>,
+    board: Board,
+    options: Options,
+    //Shared with the search thread, which holds it until the search ends
+    table: Arc<Mutex<TranspositionTable>>,
+    stop: Arc<AtomicBool>,
+    search: Option<JoinHandle<()>>,
+}
+
+/// Runs the UCI loop until `quit` or the end of the input.
+pub fn run<R: BufRead, W: Write + Send + 'static>(input: R, output: W) {
+    let mut uci = Uci {
+        out: Arc::new(Mutex::new(output)),
+        board: Board::default(),
+        options: Options::default(),
+        table: Arc::new(Mutex::new(TranspositionTable::new(DEFAULT_HASH))),
+        stop: Arc::new(AtomicBool::new(false)),
+        search: None,
+    };
+    for line in input.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if !uci.handle(&line) {
+            break;
+        }
+    }
+    uci.stop_search();
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+    //Returns false once the engine should quit
+    fn handle(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first() {
+            Some(&"uci") => {
+                self.send(&format!("id name {}", NAME));
+                self.send(&format!("id author {}", AUTHOR));
+                self.send(&format!(
+                    "option name Hash type spin default {} min 1 max {}",
+                    DEFAULT_HASH, MAX_HASH
+                ));
+                self.send("option name Move Overhead type spin default 10 min 0 max 5000");
+                self.send("uciok");
+            }
+            Some(&"isready") => self.send("readyok"),
+            Some(&"ucinewgame") => {
+                self.stop_search();
+                self.board = Board::default();
+                self.table().clear();
+            }
+            Some(&"setoption") => self.set_option(&tokens[1..]),
+            Some(&"position") => {
+                self.stop_search();
+                self.position(&tokens[1..]);
+            }
+            Some(&"go") => {
+                self.stop_search();
+                self.go(&tokens[1..]);
+            }
+            Some(&"stop") => self.stop_search(),
+            //Not part of UCI, prints the evaluation of the current position
+            Some(&"eval") => {
+                for line in self.board.eval_trace().to_string().lines() {
+                    self.send(line);
+                }
+            }
+            Some(&"quit") => return false,
+            //Unknown commands are ignored as the protocol requires
+            _ => (),
+        }
+        true
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.out, line);
+    }
+
+    fn table(&self) -> MutexGuard<'_, TranspositionTable> {
+        lock(&self.table)
+    }
+
+    fn set_option(&mut self, tokens: &[&str]) {
+        let value_index = tokens.iter().position(|&t| t == "value");
+        let name = tokens[..value_index.unwrap_or(tokens.len())]
+            .iter()
+            .skip_while(|&&t| t == "name")
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let value = value_index.map(|i| tokens[i + 1..].join(" "));
+        match (name.to_lowercase().as_str(), value) {
+            ("hash", Some(value)) => match value.parse::<usize>() {
+                Ok(megabytes) => {
+                    self.stop_search();
+                    self.table().resize(megabytes.clamp(1, MAX_HASH));
+                }
+                _ => self.send(&format!("info string invalid value {}", value)),
+            },
+            ("move overhead", Some(value)) => match value.parse() {
+                Ok(overhead) => self.options.move_overhead = overhead,
+                Err(_) => self.send(&format!("info string invalid value {}", value)),
+            },
+            _ => self.send(&format!("info string unknown option {}", name)),
+        }
+    }
+
+    fn position(&mut self, tokens: &[&str]) {
+        let moves_index = tokens.iter().position(|&t| t == "moves");
+        let setup = &tokens[..moves_index.unwrap_or(tokens.len())];
+        let board = match setup.first() {
+            Some(&"startpos") => Board::from_fen(START_FEN),
+            Some(&"fen") => Board::from_fen(&setup[1..].join(" ")),
+            _ => {
+                self.send("info string expected startpos or fen");
+                return;
+            }
+        };
+        self.board = match board {
+            Ok(board) => board,
+            Err(e) => {
+                self.send(&format!("info string {}", e));
+                return;
+            }
+        };
+        let moves = match moves_index {
+            Some(i) => &tokens[i + 1..],
+            None => &[],
+        };
+        for m in moves {
+            match self.board.parse_uci_move(m) {
+                Ok(board_move) => self.board.make_move(board_move),
+                Err(e) => {
+                    self.send(&format!("info string {} {}", e, m));
+                    return;
+                }
+            }
+        }
+    }
+
+    fn go(&mut self, tokens: &[&str]) {
+        let params = parse_go(tokens);
+        if let Some(depth) = params.perft {
+            let divide = self.board.perft_divide(depth, &mut lock(&self.table));
+            for (board_move, nodes) in &divide {
+                self.send(&format!("{}: {}", board_move.to_uci(), nodes));
+            }
+            let total: usize = divide.iter().map(|(_, nodes)| nodes).sum();
+            self.send("");
+            self.send(&format!("Nodes searched: {}", total));
+            return;
+        }
+        let limits = SearchLimits {
+            depth: params.depth,
+            movetime: self.allocate_time(&params),
+            nodes: params.nodes,
+        };
+        let mut board = self.board.clone();
+        let mut moves = MoveList::new();
+        board.legal_moves(&mut moves);
+        let fallback = moves.iter().next();
+        let out = Arc::clone(&self.out);
+        let stop = Arc::clone(&self.stop);
+        let table = Arc::clone(&self.table);
+        let infinite = params.infinite;
+        self.search = Some(thread::spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut table = lock(&table);
+                board.search(&limits, &mut table, &stop, |result| {
+                    send(&out, &info(result))
+                })
+            }));
+            //bestmove may only be sent after stop when searching infinitely
+            while infinite && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            send_best_move(&out, result, fallback);
+        }));
+    }
+
+    fn allocate_time(&self, params: &GoParams) -> Option<Duration> {
+        if params.infinite {
+            return None;
+        }
+        let overhead = self.options.move_overhead;
+        if let Some(movetime) = params.movetime {
+            return Some(Duration::from_millis(movetime.saturating_sub(overhead)));
+        }
+        let (time, inc) = if self.board.side_to_move().is_white() {
+            (params.wtime?, params.winc.unwrap_or(0))
+        } else {
+            (params.btime?, params.binc.unwrap_or(0))
+        };
+        let moves_to_go = params.movestogo.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let budget = (time / moves_to_go + inc * 3 / 4).min(time.saturating_sub(overhead));
+        Some(Duration::from_millis(budget.saturating_sub(overhead)))
+    }
+
+    fn stop_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.stop.store(true, Ordering::Relaxed);
+            //Keep serving the GUI rather than going down with the search
+            if search.join().is_err() {
+                self.send("info string search failed");
+            }
+        }
+        self.stop.store(false, Ordering::Relaxed);
+    }
+}
+
+//The GUI waits for a bestmove whatever happens, so a failed search still
+//answers with any legal move
+fn send_best_move<W: Write>(
+    out: &Mutex<W>,
+    result: Result<SearchResult, Box<dyn Any + Send>>,
+    fallback: Option<Move>,
+) {
+    let best_move = match result {
+        Ok(result) => result.best_move,
+        Err(_) => {
+            send(out, "info string search failed");
+            fallback
+        }
+    };
+    match best_move {
+        Some(best_move) => send(out, &format!("bestmove {}", best_move.to_uci())),
+        None => send(out, "bestmove 0000"),
+    }
+}
+
+//A search that panicked poisons the table, but its entries are still usable
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn send<W: Write>(out: &Mutex<W>, line: &str) {
+    let mut out = out.lock().unwrap();
+    //Nothing sensible can be done if the GUI went away
+    let _ = writeln!(out, "{}", line);
+    let _ = out.flush();
+}
+
+fn info(result: &SearchResult) -> String {
+    let score = match result.mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", result.score),
+    };
+    let millis = result.time.as_millis() as u64;
+    let nps = result.nodes * 1000 / millis.max(1);
+    let pv: Vec<String> = result.pv.iter().map(|m| m.to_uci()).collect();
+    format!(
+        "info depth {} score {} nodes {} nps {} time {} pv {}",
+        result.depth,
+        score,
+        result.nodes,
+        nps,
+        millis,
+        pv.join(" ")
+    )
+}
+
+fn parse_go(tokens: &[&str]) -> GoParams {
+    let mut params = GoParams::default();
+    let mut tokens = tokens.iter();
+    while let Some(&token) = tokens.next() {
+        let mut value = || tokens.next().and_then(|v| v.parse::<u64>().ok());
+        match token {
+            "depth" => params.depth = value().map(|d| d as usize),
+            "movetime" => params.movetime = value(),
+            "wtime" => params.wtime = value(),
+            "btime" => params.btime = value(),
+            "winc" => params.winc = value(),
+            "binc" => params.binc = value(),
+            "movestogo" => params.movestogo = value(),
+            "nodes" => params.nodes = value(),
+            "perft" => params.perft = value().map(|d| d as usize),
+            "infinite" => params.infinite = true,
+            _ => (),
+        }
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor, Write};
+    use std::sync::{Arc, Mutex};
+
+    //Output the test can still read after `run` has taken the writer
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run(commands: &str) -> Vec<String> {
+        let output = Output::default();
+        super::run(Cursor::new(commands.to_string()), output.clone());
+        let bytes = output.0.lock().unwrap().clone();
+        String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn handshake() {
+        let lines = run("uci\nisready\nquit\n");
+        assert!(lines[0].starts_with("id name "), "{:?}", lines);
+        assert!(lines[1].starts_with("id author "), "{:?}", lines);
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("option name Hash ")));
+        let uciok = lines.iter().position(|line| line == "uciok").unwrap();
+        assert_eq!(lines[uciok + 1..], ["readyok"]);
+    }
+
+    #[test]
+    fn searches_the_position_after_moves() {
+        //Mate in one once the king has stepped into the corner
+        let lines =
+            run("position fen 6k1/8/5K2/8/8/8/8/R7 w - - 0 1 moves f6g6 g8h8\ngo depth 2\n");
+        assert!(
+            lines.iter().any(|line| line.starts_with("info depth 1 ")),
+            "{:?}",
+            lines
+        );
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("bestmove a1a8"),
+            "{:?}",
+            lines
+        );
+
+        let lines = run("position startpos moves e2e4 e7e5 g1f3\ngo depth 2\n");
+        let best = lines.last().unwrap().strip_prefix("bestmove ").unwrap();
+        let mut board = chessmate::Board::new();
+        for uci in ["e2e4", "e7e5", "g1f3"] {
+            board.make_move(board.parse_uci_move(uci).unwrap());
+        }
+        assert!(board.parse_uci_move(best).is_ok(), "{}", best);
+    }
+
+    #[test]
+    fn bad_input_is_reported() {
+        let lines = run("position startpos moves e2e4 e2e4\nposition fen 7k/8/8/8/8/8/8/K6R w - - 0 1\nisready\n");
+        assert_eq!(
+            lines,
+            [
+                "info string illegal move e2e4",
+                "info string side not to move is in check in FEN",
+                "readyok",
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_searches_still_send_bestmove() {
+        let lines = |result, fallback| {
+            let out = Mutex::new(Vec::new());
+            super::send_best_move(&out, result, fallback);
+            String::from_utf8(out.into_inner().unwrap()).unwrap()
+        };
+        let board = chessmate::Board::new();
+        let e4 = board.parse_uci_move("e2e4").unwrap();
+        assert_eq!(
+            lines(Err(Box::new("boom")), Some(e4)),
+            "info string search failed\nbestmove e2e4\n"
+        );
+        assert_eq!(
+            lines(Err(Box::new("boom")), None),
+            "info string search failed\nbestmove 0000\n"
+        );
+    }
+
+    #[test]
+    fn hash_size_is_clamped() {
+        let lines = run("setoption name Hash value 0\nsetoption name Hash value -1\nisready\n");
+        assert_eq!(lines, ["info string invalid value -1", "readyok"]);
+    }
+}
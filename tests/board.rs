@@ -0,0 +1,291 @@
+use chessmate::{Board, Color, FenError, Move, MoveList, Piece, Square, UciMoveError, START_FEN};
+
+fn find_move(board: &Board, uci: &str) -> Move {
+    board.parse_uci_move(uci).unwrap()
+}
+
+#[test]
+fn promotion_replaces_pawn() {
+    let fen = "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    for (uci, piece) in [
+        ("b7b8q", 'Q'),
+        ("b7b8r", 'R'),
+        ("b7b8b", 'B'),
+        ("b7b8n", 'N'),
+    ] {
+        let promotion = find_move(&board, uci);
+        board.make_move(promotion);
+        assert_eq!(
+            board.to_fen(),
+            format!("1{}2k3/8/8/8/8/8/8/4K3 b - - 0 1", piece)
+        );
+        board.undo_move(promotion);
+        assert_eq!(board.to_fen(), fen);
+    }
+}
+
+#[test]
+fn capture_promotion_restores_captured_piece() {
+    let fen = "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let promotion = find_move(&board, "g2h1q");
+    board.make_move(promotion);
+    assert_eq!(board.to_fen(), "n1n5/PPPk4/8/8/8/8/4Kp1p/5N1q w - - 0 2");
+    board.undo_move(promotion);
+    assert_eq!(board.to_fen(), fen);
+}
+
+#[test]
+fn castling_rights_are_lost() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let cases = [
+        ("e1e2", "r3k2r/8/8/8/8/8/4K3/R6R b kq - 1 1"),
+        ("h1h2", "r3k2r/8/8/8/8/8/7R/R3K3 b Qkq - 1 1"),
+        ("a1a8", "R3k2r/8/8/8/8/8/8/4K2R b Kk - 0 1"),
+    ];
+    for (uci, expected) in cases {
+        let mut board = Board::from_fen(fen).unwrap();
+        let board_move = find_move(&board, uci);
+        board.make_move(board_move);
+        assert_eq!(board.to_fen(), expected);
+        board.undo_move(board_move);
+        assert_eq!(board.to_fen(), fen);
+    }
+}
+
+#[test]
+fn transpositions_share_a_hash() {
+    let mut a = Board::default();
+    let mut b = Board::default();
+    for uci in ["g1f3", "g8f6", "b1c3", "b8c6"] {
+        let board_move = find_move(&a, uci);
+        a.make_move(board_move);
+    }
+    for uci in ["b1c3", "b8c6", "g1f3", "g8f6"] {
+        let board_move = find_move(&b, uci);
+        b.make_move(board_move);
+    }
+    assert_eq!(a.hash(), b.hash());
+    assert_ne!(a.hash(), Board::default().hash());
+}
+
+#[test]
+fn start_position() {
+    let board = Board::new();
+    assert_eq!(board.to_fen(), START_FEN);
+    assert_eq!(board.side_to_move(), Color::White);
+    assert!(!board.is_check());
+    let mut moves = MoveList::new();
+    board.legal_moves(&mut moves);
+    assert_eq!(moves.len(), 20);
+}
+
+#[test]
+fn piece_on_reports_colour_and_piece() {
+    let board = Board::new();
+    let square = |name: &str| name.parse::<Square>().unwrap();
+    assert_eq!(
+        board.piece_on(square("e1")),
+        Some((Color::White, Piece::King))
+    );
+    assert_eq!(
+        board.piece_on(square("d8")),
+        Some((Color::Black, Piece::Queen))
+    );
+    assert_eq!(
+        board.piece_on(square("g7")),
+        Some((Color::Black, Piece::Pawn))
+    );
+    assert_eq!(board.piece_on(square("e4")), None);
+}
+
+#[test]
+fn squares_round_trip() {
+    for index in 0..64 {
+        let square = Square::from_index(index).unwrap();
+        assert_eq!(square.index(), index);
+        assert_eq!(square.to_string().parse::<Square>(), Ok(square));
+        assert_eq!(Square::new(square.file(), square.rank()), Some(square));
+    }
+    assert_eq!("e4".parse::<Square>().unwrap().index(), 28);
+    assert_eq!(Square::from_index(64), None);
+    assert_eq!(Square::new(8, 0), None);
+    for bad in ["", "e", "i1", "e9", "e44", "E4"] {
+        assert!(bad.parse::<Square>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn moves_report_their_squares() {
+    let mut board = Board::new();
+    let double_push = find_move(&board, "e2e4");
+    assert_eq!(double_push.from(), "e2".parse().unwrap());
+    assert_eq!(double_push.to(), "e4".parse().unwrap());
+    board.make_move(double_push);
+    assert_eq!(board.side_to_move(), !Color::White);
+    assert_eq!(
+        board.piece_on(double_push.to()),
+        Some((Color::White, Piece::Pawn))
+    );
+    assert_eq!(board.piece_on(double_push.from()), None);
+    board.undo_move(double_push);
+    assert_eq!(board.to_fen(), START_FEN);
+}
+
+#[test]
+fn uci_moves_parse_and_format() {
+    let board = Board::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let castle = board.parse_uci_move("e1g1").unwrap();
+    assert!(castle.is_castle());
+    //Internally the king moves onto its rook
+    assert_eq!(castle.to(), "h1".parse().unwrap());
+    assert_eq!(castle.to_uci(), "e1g1");
+    assert_eq!(board.parse_uci_move("e1c1").unwrap().to_uci(), "e1c1");
+    let promotion = board.parse_uci_move("b7a8n").unwrap();
+    assert_eq!(promotion.promotion(), Piece::Knight);
+    assert!(promotion.is_capture());
+
+    let mut moves = MoveList::new();
+    board.legal_moves(&mut moves);
+    for board_move in moves.iter() {
+        assert_eq!(board.parse_uci_move(&board_move.to_uci()), Ok(board_move));
+    }
+}
+
+#[test]
+fn bad_uci_moves_are_rejected() {
+    let board = Board::new();
+    assert_eq!(board.parse_uci_move("e2e5"), Err(UciMoveError::Illegal));
+    assert_eq!(board.parse_uci_move("e1g1"), Err(UciMoveError::Illegal));
+    //A promotion without its piece, or a piece without a promotion
+    let promotion = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(promotion.parse_uci_move("b7b8"), Err(UciMoveError::Illegal));
+    assert_eq!(board.parse_uci_move("e2e4q"), Err(UciMoveError::Illegal));
+    for bad in ["", "e2", "e2e4e", "i2i4", "e2e4k", "e2e9", "é2e4"] {
+        assert_eq!(
+            board.parse_uci_move(bad),
+            Err(UciMoveError::Syntax),
+            "{}",
+            bad
+        );
+    }
+}
+
+#[test]
+fn impossible_positions_are_rejected() {
+    //The side that just moved is in check, so its king could be taken
+    for fen in [
+        "7k/8/8/8/8/8/8/K6R w - - 0 1",
+        "k7/8/8/8/8/8/8/r6K b - - 0 1",
+        "8/8/8/3kK3/8/8/8/8 w - - 0 1",
+        "4k3/3P4/8/8/8/8/8/4K3 w - - 0 1",
+    ] {
+        assert_eq!(
+            Board::from_fen(fen).err(),
+            Some(FenError::OpponentInCheck),
+            "{}",
+            fen
+        );
+    }
+    //Being in check on your own move is fine
+    assert!(Board::from_fen("7k/8/8/8/8/8/8/K6R b - - 0 1").is_ok());
+
+    for fen in [
+        "4k3/8/8/8/8/8/8/P3K3 w - - 0 1",
+        "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4K2p b - - 0 1",
+    ] {
+        assert_eq!(
+            Board::from_fen(fen).err(),
+            Some(FenError::PiecePlacement),
+            "{}",
+            fen
+        );
+    }
+}
+
+#[test]
+fn en_passant_needs_a_pawn_that_just_moved() {
+    for fen in [
+        //No pawn on e5 to take
+        "4k3/8/8/3P4/8/8/8/4K3 w - e6 0 1",
+        //The pawn couldn't have passed through e6 or come from e7
+        "4k3/8/4n3/3Pp3/8/8/8/4K3 w - e6 0 1",
+        "4k3/4n3/8/3Pp3/8/8/8/4K3 w - e6 0 1",
+        //White's own pawn on the square
+        "4k3/8/8/3PP3/8/8/8/4K3 w - e6 0 1",
+        "4k3/8/8/8/3p4/8/8/4K3 b - e3 0 1",
+    ] {
+        assert_eq!(
+            Board::from_fen(fen).err(),
+            Some(FenError::EnPassant),
+            "{}",
+            fen
+        );
+    }
+    let fen = "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let capture = find_move(&board, "d5e6");
+    board.make_move(capture);
+    assert_eq!(board.to_fen(), "4k3/8/4P3/8/8/8/8/4K3 b - - 0 1");
+    board.undo_move(capture);
+    assert_eq!(board.to_fen(), fen);
+    assert!(Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").is_ok());
+}
+
+#[test]
+fn fens_round_trip() {
+    for fen in [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "r3k3/8/8/8/4pP2/8/8/4K2R b Kq f3 12 40",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+    //The clocks are optional and default to the start of the game
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 b -").err(),
+        Some(FenError::MissingFields)
+    );
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - -")
+            .unwrap()
+            .to_fen(),
+        "4k3/8/8/8/8/8/8/4K3 b - - 0 1"
+    );
+}
+
+#[test]
+fn bad_fen_fields_are_reported() {
+    let cases = [
+        ("", FenError::MissingFields),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra",
+            FenError::TooManyFields,
+        ),
+        ("4k3/8/8/8/8/8/8 w - - 0 1", FenError::PiecePlacement),
+        ("4k3/8/8/8/8/8/8/4K4 w - - 0 1", FenError::PiecePlacement),
+        ("4k3/8/8/8/8/8/8/4X3 w - - 0 1", FenError::PiecePlacement),
+        ("4k3/8/8/8/8/8/8/8 w - - 0 1", FenError::PiecePlacement),
+        ("4k3/8/8/8/8/8/8/4K3 white - - 0 1", FenError::ActiveColor),
+        ("4k3/8/8/8/8/8/8/4K3 w x - 0 1", FenError::Castling),
+        ("4k3/8/8/8/8/8/8/4K3 w K - 0 1", FenError::Castling),
+        ("4k3/8/8/8/8/8/8/4K2R w KK - 0 1", FenError::Castling),
+        ("4k3/8/8/8/8/8/8/4K3 w - e9 0 1", FenError::EnPassant),
+        ("4k3/8/8/8/8/8/8/4K3 w - e3 0 1", FenError::EnPassant),
+        ("4k3/8/8/8/8/8/8/4K3 w - - x 1", FenError::HalfmoveClock),
+        ("4k3/8/8/8/8/8/8/4K3 w - - -1 1", FenError::HalfmoveClock),
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 0", FenError::FullmoveNumber),
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 x", FenError::FullmoveNumber),
+    ];
+    for (fen, error) in cases {
+        assert_eq!(Board::from_fen(fen).err(), Some(error), "{}", fen);
+    }
+    assert_eq!(
+        FenError::Castling.to_string(),
+        "invalid castling availability field in FEN"
+    );
+}
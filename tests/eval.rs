@@ -0,0 +1,209 @@
+use chessmate::{Board, Score, START_FEN};
+
+const POSITIONS: [&str; 6] = [
+    START_FEN,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+fn evaluate(fen: &str) -> i32 {
+    Board::from_fen(fen).unwrap().evaluate()
+}
+
+//Colours swapped and the board turned upside down. Castling and en passant
+//don't matter to the evaluation so they are dropped.
+fn mirror(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let ranks: Vec<String> = fields[0]
+        .split('/')
+        .rev()
+        .map(|rank| {
+            rank.chars()
+                .map(|c| {
+                    if c.is_ascii_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let side = if fields[1] == "w" { "b" } else { "w" };
+    format!("{} {} - - 0 1", ranks.join("/"), side)
+}
+
+fn with_side(fen: &str, side: &str) -> String {
+    let mut fields: Vec<&str> = fen.split_whitespace().collect();
+    fields[1] = side;
+    fields[3] = "-";
+    fields.join(" ")
+}
+
+#[test]
+fn start_position_is_balanced() {
+    assert_eq!(evaluate(START_FEN), 0);
+    assert_eq!(Board::new().phase(), 24);
+}
+
+#[test]
+fn evaluation_is_symmetric() {
+    for fen in POSITIONS {
+        assert_eq!(evaluate(fen), evaluate(&mirror(fen)), "{}", fen);
+        //Relative to the side to move, where the other side isn't left in check
+        let white = Board::from_fen(&with_side(fen, "w"));
+        let black = Board::from_fen(&with_side(fen, "b"));
+        if let (Ok(white), Ok(black)) = (white, black) {
+            assert_eq!(white.evaluate(), -black.evaluate(), "{}", fen);
+        }
+    }
+}
+
+#[test]
+fn phase_follows_material() {
+    let phase = |fen: &str| Board::from_fen(fen).unwrap().phase();
+    assert_eq!(phase("4k3/pppppppp/8/8/8/8/PPPPPPPP/4K3 w - - 0 1"), 0);
+    assert_eq!(phase("3qk3/8/8/8/8/8/8/1N2KB2 w - - 0 1"), 6);
+    assert_eq!(phase("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1"), 8);
+    //Promoted queens don't push it past the start
+    assert_eq!(phase("qqqqkqqq/8/8/8/8/8/8/QQQQKQQQ w - - 0 1"), 24);
+}
+
+#[test]
+fn pieces_prefer_good_squares() {
+    //A centralised knight beats one in the corner
+    assert!(
+        evaluate("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1") > evaluate("4k3/8/8/8/8/8/8/N3K3 w - - 0 1")
+    );
+    //Pawns are worth more as they advance, more so in the endgame
+    let advanced = evaluate("4k3/8/1P6/8/8/8/8/4K3 w - - 0 1");
+    let home = evaluate("4k3/8/8/8/8/8/1P6/4K3 w - - 0 1");
+    assert!(advanced > home + 50, "{} {}", advanced, home);
+    //The king hides in the middlegame and comes out in the endgame
+    assert!(evaluate("4k3/8/8/8/3K4/8/8/8 w - - 0 1") > evaluate("4k3/8/8/8/8/8/8/6K1 w - - 0 1"));
+    assert!(
+        evaluate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQ1RK1 w kq - 0 1")
+            > evaluate("rnbqkbnr/pppppppp/8/8/8/4K3/PPPPPPPP/RNBQ1R2 w kq - 0 1")
+    );
+}
+
+#[test]
+fn material_still_counts_most() {
+    let up_a_knight = evaluate("rnbqkb1r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert!(up_a_knight > 250, "{}", up_a_knight);
+    //The old heuristic is still there to compare against
+    let board =
+        Board::from_fen("rnbqkb1r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    assert!(board.hueristic() > 2.5);
+}
+
+#[test]
+fn pawn_structure_counts() {
+    //Same material, the pawns split up or stacked on one file
+    let healthy = evaluate("4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1");
+    let doubled = evaluate("4k3/pppp4/8/8/8/2P5/PP1P4/4K3 w - - 0 1");
+    let isolated = evaluate("4k3/pppp4/8/8/8/8/PP1P1P2/4K3 w - - 0 1");
+    assert!(healthy > doubled, "{} {}", healthy, doubled);
+    assert!(healthy > isolated, "{} {}", healthy, isolated);
+
+    //A passed pawn beats one the enemy pawns can stop
+    let passed = evaluate("7k/p7/4P3/8/8/8/8/7K w - - 0 1");
+    let stoppable = evaluate("7k/3p4/4P3/8/8/8/8/7K w - - 0 1");
+    assert!(passed > stoppable + 50, "{} {}", passed, stoppable);
+
+    //And is worth more with our king escorting it than with theirs in front
+    let escorted = evaluate("8/8/2k5/4K3/4P3/8/8/8 w - - 0 1");
+    let stopped = evaluate("8/4k3/8/8/4P3/8/8/K7 w - - 0 1");
+    assert!(escorted > stopped, "{} {}", escorted, stopped);
+}
+
+#[test]
+fn pawn_structure_is_symmetric() {
+    for fen in [
+        "4k3/1pp2p1p/p3p3/3P4/2P5/1P3PP1/P6P/4K3 w - - 0 1",
+        "6k1/5pp1/3p3p/2pP4/2P1P3/1p6/6PP/6K1 b - - 0 1",
+    ] {
+        assert_eq!(evaluate(fen), evaluate(&mirror(fen)), "{}", fen);
+    }
+}
+
+#[test]
+fn king_safety_counts() {
+    //Pawns in front of the castled king
+    let sheltered = evaluate("r4rk1/ppp2ppp/8/8/8/8/PPP2PPP/R4RK1 w - - 0 1");
+    let pushed = evaluate("r4rk1/ppp2ppp/8/8/8/5PPP/PPP5/R4RK1 w - - 0 1");
+    assert!(sheltered > pushed, "{} {}", sheltered, pushed);
+
+    //An open file in front of the king
+    let closed = evaluate("r4rk1/ppp2ppp/8/8/8/8/PPP2PPP/R4RK1 w - - 0 1");
+    let open = evaluate("r4rk1/ppp2ppp/8/8/8/8/PPPP1P1P/R4RK1 w - - 0 1");
+    assert!(closed > open, "{} {}", closed, open);
+
+    //Pieces swarming the king are worth more together than apart
+    let quiet = evaluate("r1b2rk1/ppp2ppp/8/q7/1n6/2N5/PPP2PPP/R1BQR1K1 w - - 0 1");
+    let attack = evaluate("r1b2rk1/ppp2ppp/8/8/6nq/2N5/PPP2PPP/R1BQR1K1 w - - 0 1");
+    assert!(quiet > attack + 30, "{} {}", quiet, attack);
+}
+
+#[test]
+fn mobility_counts() {
+    //A bishop hemmed in by its own pawns against one on an open diagonal
+    let free = evaluate("4k3/8/8/8/8/1P6/P1P5/B3K3 w - - 0 1");
+    let trapped = evaluate("4k3/8/8/8/8/8/PPP5/1B2K3 w - - 0 1");
+    assert!(free > trapped, "{} {}", free, trapped);
+
+    //Squares covered by enemy pawns don't count
+    let open = evaluate("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+    let covered = evaluate("4k3/8/8/8/1p6/8/8/R3K3 w - - 0 1")
+        - evaluate("4k3/8/8/8/1p6/8/8/4K3 w - - 0 1")
+        - (open - evaluate("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+    assert!(covered < 0, "{}", covered);
+}
+
+#[test]
+fn trace_adds_up_to_the_evaluation() {
+    for fen in POSITIONS {
+        for side in ["w", "b"] {
+            let Ok(board) = Board::from_fen(&with_side(fen, side)) else {
+                continue;
+            };
+            let trace = board.eval_trace();
+            let score = if side == "w" {
+                trace.score()
+            } else {
+                -trace.score()
+            };
+            assert_eq!(score, board.evaluate(), "{}", fen);
+            assert_eq!(trace.phase, board.phase());
+        }
+    }
+
+    let trace = Board::from_fen("4k3/8/8/8/8/8/P7/4K3 w - - 0 1")
+        .unwrap()
+        .eval_trace();
+    let term = |name| trace.terms.iter().find(|term| term.name == name).unwrap();
+    assert_eq!(term("Material").white, Score { mg: 82, eg: 94 });
+    assert_eq!(term("Material").black, Score::default());
+    assert!(term("Passed pawns").white.eg > 0);
+    assert!(term("Isolated pawns").white.eg < 0);
+}
+
+#[test]
+fn trace_prints_as_a_table() {
+    let table = Board::new().eval_trace().to_string();
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].starts_with("Term"), "{}", table);
+    assert!(table.contains("\nMaterial         |   4039   3868 |   4039   3868 |      0      0\n"));
+    assert!(
+        lines.iter().all(|line| line == &line.trim_end()),
+        "{}",
+        table
+    );
+    assert_eq!(
+        lines.last(),
+        Some(&"Evaluation: +0 cp, white's point of view")
+    );
+}
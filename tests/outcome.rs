@@ -0,0 +1,169 @@
+use chessmate::{Board, Color, MoveList, Outcome};
+
+fn outcome(fen: &str) -> Outcome {
+    Board::from_fen(fen).unwrap().outcome()
+}
+
+#[test]
+fn start_position_is_ongoing() {
+    let board = Board::new();
+    assert_eq!(board.outcome(), Outcome::Ongoing);
+    assert!(!board.outcome().is_game_over());
+    assert!(!board.is_check());
+}
+
+#[test]
+fn checkmate_names_the_winner() {
+    //Fool's mate
+    let fools_mate = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+    assert_eq!(outcome(fools_mate), Outcome::Checkmate(Color::Black));
+    assert!(Board::from_fen(fools_mate).unwrap().is_check());
+    //Back rank
+    let back_rank = "R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1";
+    assert_eq!(outcome(back_rank), Outcome::Checkmate(Color::White));
+    assert_eq!(outcome(back_rank).winner(), Some(Color::White));
+    //Smothered
+    assert_eq!(
+        outcome("6rk/5Npp/8/8/8/8/8/6K1 b - - 1 1"),
+        Outcome::Checkmate(Color::White)
+    );
+}
+
+#[test]
+fn stalemate_is_not_checkmate() {
+    let stalemate = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1";
+    assert_eq!(outcome(stalemate), Outcome::Stalemate);
+    assert!(!Board::from_fen(stalemate).unwrap().is_check());
+    assert_eq!(outcome(stalemate).winner(), None);
+    assert!(outcome(stalemate).is_game_over());
+    //Pinned pieces and blocked pawns can't move either
+    assert_eq!(
+        outcome("8/8/8/8/8/p7/P1k5/K1b5 w - - 0 1"),
+        Outcome::Stalemate
+    );
+}
+
+#[test]
+fn check_with_an_escape_is_ongoing() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+    assert!(board.is_check());
+    assert_eq!(board.outcome(), Outcome::Ongoing);
+}
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for uci in moves {
+        board.make_move(board.parse_uci_move(uci).unwrap());
+    }
+}
+
+#[test]
+fn halfmove_clock_resets_on_pawn_moves_and_captures() {
+    let mut board = Board::new();
+    play(&mut board, &["g1f3", "b8c6"]);
+    assert_eq!(board.halfmove_clock(), 2);
+    assert_eq!(board.fullmove_number(), 2);
+    play(&mut board, &["e2e4"]);
+    assert_eq!(board.halfmove_clock(), 0);
+    play(&mut board, &["c6d4", "f3d4"]);
+    assert_eq!(board.halfmove_clock(), 0);
+    assert_eq!(board.fullmove_number(), 3);
+    assert_eq!(
+        board.to_fen(),
+        "r1bqkbnr/pppppppp/8/8/3NP3/8/PPPP1PPP/RNBQKB1R b KQkq - 0 3"
+    );
+}
+
+#[test]
+fn undo_restores_the_clocks() {
+    let fen = "4k3/8/8/8/8/8/4P3/R3K3 b - - 37 60";
+    let mut board = Board::from_fen(fen).unwrap();
+    let mut moves = MoveList::new();
+    board.legal_moves(&mut moves);
+    for board_move in moves.iter() {
+        board.make_move(board_move);
+        let mut replies = MoveList::new();
+        board.legal_moves(&mut replies);
+        for reply in replies.iter() {
+            board.make_move(reply);
+            board.undo_move(reply);
+        }
+        board.undo_move(board_move);
+        assert_eq!(board.to_fen(), fen);
+    }
+}
+
+#[test]
+fn threefold_repetition() {
+    let mut board = Board::new();
+    let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    assert!(board.is_repetition(1));
+    assert!(!board.is_repetition(2));
+    play(&mut board, &shuffle);
+    assert!(board.is_repetition(2));
+    assert!(!board.is_repetition(3));
+    assert_eq!(board.outcome(), Outcome::Ongoing);
+    play(&mut board, &shuffle);
+    assert!(board.is_repetition(3));
+    assert_eq!(board.outcome(), Outcome::ThreefoldRepetition);
+    assert!(board.outcome().is_draw());
+    //An irreversible move starts the count again
+    play(&mut board, &["e2e4", "e7e5"]);
+    assert!(!board.is_repetition(2));
+    play(&mut board, &shuffle);
+    assert!(board.is_repetition(2));
+    assert!(!board.is_repetition(3));
+}
+
+#[test]
+fn repetition_needs_the_same_rights() {
+    //The kings come back but castling rights are gone
+    let mut board = Board::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+    play(&mut board, &["e1f1", "e8f8", "f1e1", "f8e8"]);
+    assert!(!board.is_repetition(2));
+    play(&mut board, &["e1f1", "e8f8", "f1e1", "f8e8"]);
+    assert!(board.is_repetition(2));
+}
+
+#[test]
+fn fifty_move_rule() {
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+    assert!(!board.is_fifty_move_draw());
+    play(&mut board, &["a1a2"]);
+    assert!(board.is_fifty_move_draw());
+    assert_eq!(board.outcome(), Outcome::FiftyMoveRule);
+    //Mate on the last move still wins
+    let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 99 80").unwrap();
+    play(&mut board, &["a1a8"]);
+    assert!(board.is_fifty_move_draw());
+    assert_eq!(board.outcome(), Outcome::Checkmate(Color::White));
+}
+
+#[test]
+fn insufficient_material() {
+    let insufficient = |fen: &str| Board::from_fen(fen).unwrap().is_insufficient_material();
+    //Bare kings and a single minor piece
+    assert!(insufficient("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+    assert!(insufficient("4k3/8/8/8/8/8/8/4KN2 w - - 0 1"));
+    assert!(insufficient("4kb2/8/8/8/8/8/8/4K3 b - - 0 1"));
+    //Bishops all on one colour, however many
+    assert!(insufficient("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+    assert!(insufficient("4k3/8/8/8/8/8/1B6/B1B1K3 w - - 0 1"));
+    //Opposite coloured bishops can mate
+    assert!(!insufficient("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1"));
+    assert!(!insufficient("4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+    //So can two knights, or a knight and a bishop, with help
+    assert!(!insufficient("4k3/8/8/8/8/8/8/1N2K1N1 w - - 0 1"));
+    assert!(!insufficient("4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+    for fen in [
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        "3qk3/8/8/8/8/8/8/4K3 w - - 0 1",
+    ] {
+        assert!(!insufficient(fen), "{}", fen);
+    }
+    assert_eq!(
+        outcome("4k3/8/8/8/8/8/8/4KN2 w - - 0 1"),
+        Outcome::InsufficientMaterial
+    );
+    assert!(Outcome::InsufficientMaterial.is_draw());
+}
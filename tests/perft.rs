@@ -0,0 +1,211 @@
+use chessmate::{Board, Square, TranspositionTable, START_FEN};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str =
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn perft(fen: &str, depth: usize) -> usize {
+    let mut board = Board::from_fen(fen).unwrap();
+    let nodes = board.perft(depth);
+    assert_eq!(board.to_fen(), fen, "perft did not restore the position");
+    nodes
+}
+
+//Checks every depth from 1 up to the length of `expected`
+fn assert_perft(fen: &str, expected: &[usize]) {
+    for (depth, &nodes) in expected.iter().enumerate() {
+        assert_eq!(
+            perft(fen, depth + 1),
+            nodes,
+            "{} at depth {}",
+            fen,
+            depth + 1
+        );
+    }
+}
+
+#[test]
+fn perft_promotions() {
+    assert_perft(
+        "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+        &[24, 496, 9483, 182838],
+    );
+}
+
+#[test]
+fn perft_promotion_endgames() {
+    assert_eq!(perft("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6), 92683);
+    assert_eq!(perft("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6), 2217);
+    assert_eq!(perft("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6), 217342);
+    assert_eq!(perft("8/Pk6/8/8/8/8/6Kp/8 b - - 0 1", 6), 1030499);
+}
+
+#[test]
+fn perft_castling() {
+    assert_eq!(perft("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", 4), 314346);
+    assert_eq!(perft("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", 4), 314346);
+    assert_eq!(
+        perft("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4),
+        1274206
+    );
+    assert_eq!(perft("4k3/8/8/8/8/8/8/4K2R w K - 0 1", 6), 764643);
+    assert_eq!(perft("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1", 6), 846648);
+    assert_eq!(perft("4k2r/8/8/8/8/8/8/4K3 w k - 0 1", 6), 899442);
+    assert_eq!(perft("r3k3/8/8/8/8/8/8/4K3 w q - 0 1", 6), 1001523);
+}
+
+#[test]
+fn perft_zero_is_one() {
+    assert_eq!(perft(START_FEN, 0), 1);
+}
+
+#[test]
+fn perft_divide_sums_to_perft() {
+    let mut board = Board::from_fen(KIWIPETE).unwrap();
+    let divide = board.perft_divide(3, &mut TranspositionTable::new(1));
+    assert_eq!(divide.len(), 48);
+    assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<usize>(), 97862);
+    let castle = divide.iter().find(|(m, _)| m.to_uci() == "e1g1").unwrap();
+    assert_eq!(castle.1, 2059);
+}
+
+#[test]
+fn perft_start_position() {
+    assert_perft(START_FEN, &[20, 400, 8902, 197281]);
+}
+
+#[test]
+fn perft_kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862]);
+}
+
+#[test]
+fn perft_position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238, 674624]);
+}
+
+#[test]
+fn perft_position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9467]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467]);
+}
+
+#[test]
+fn perft_position_5() {
+    assert_perft(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+fn perft_position_6() {
+    assert_perft(POSITION_6, &[46, 2079, 89890]);
+}
+
+#[test]
+fn perft_en_passant() {
+    //Discovered check along the rank after taking en passant
+    assert_eq!(perft("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6), 1134888);
+    assert_eq!(perft("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6), 1015133);
+    //Taking the pawn that gives check
+    assert_eq!(perft("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6), 1440467);
+    assert_eq!(perft("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4), 23527);
+}
+
+#[test]
+fn perft_pins() {
+    assert_eq!(
+        perft("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4),
+        1720476
+    );
+    assert_eq!(perft("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6), 3821001);
+    assert_eq!(perft("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5), 1004658);
+}
+
+//Same position with the board flipped vertically and the colours swapped
+fn mirror_fen(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let swap_case = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c.to_ascii_uppercase()
+                }
+            })
+            .collect()
+    };
+    let ranks: Vec<&str> = fields[0].split('/').rev().collect();
+    let side = if fields[1] == "w" { "b" } else { "w" };
+    let mut castling: Vec<char> = swap_case(fields[2]).chars().collect();
+    castling.sort_by_key(|c| "KQkq-".find(*c));
+    let en_passant = match fields[3] {
+        "-" => "-".to_string(),
+        square => {
+            let square: Square = square.parse().unwrap();
+            Square::new(square.file(), 7 - square.rank())
+                .unwrap()
+                .to_string()
+        }
+    };
+    format!(
+        "{} {} {} {} {}",
+        swap_case(&ranks.join("/")),
+        side,
+        castling.into_iter().collect::<String>(),
+        en_passant,
+        fields[4..].join(" ")
+    )
+}
+
+#[test]
+fn perft_mirrored_positions_match() {
+    assert_eq!(mirror_fen(POSITION_4), POSITION_4_MIRRORED);
+    for fen in [
+        START_FEN,
+        KIWIPETE,
+        POSITION_3,
+        POSITION_5,
+        POSITION_6,
+        "8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1",
+        "r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1",
+    ] {
+        let mirrored = mirror_fen(fen);
+        for depth in 1..=3 {
+            assert_eq!(perft(fen, depth), perft(&mirrored, depth), "{}", mirrored);
+        }
+    }
+}
+
+//Too slow for a debug build, run with `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn perft_deep() {
+    assert_eq!(perft(START_FEN, 6), 119060324);
+    assert_eq!(perft(KIWIPETE, 5), 193690690);
+    assert_eq!(perft(POSITION_3, 7), 178633661);
+    assert_eq!(perft(POSITION_4, 5), 15833292);
+    assert_eq!(perft(POSITION_5, 5), 89941194);
+    assert_eq!(perft(POSITION_6, 5), 164075551);
+}
+
+#[test]
+fn hashed_perft_matches_perft() {
+    //Small enough that entries get replaced
+    let mut table = TranspositionTable::new(1);
+    for (fen, depth, nodes) in [
+        (KIWIPETE, 4, 4085603),
+        (POSITION_3, 5, 674624),
+        (POSITION_4, 4, 422333),
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        table.clear();
+        assert_eq!(board.perft_hashed(depth, &mut table), nodes);
+        //Second run is answered from the table
+        assert_eq!(board.perft_hashed(depth, &mut table), nodes);
+        assert_eq!(board.to_fen(), fen);
+    }
+}
@@ -0,0 +1,314 @@
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use chessmate::{
+    Board, Color, FenError, Game, Outcome, PgnErrorKind, PgnReader, SanError, SearchLimits,
+    SearchResult, TranspositionTable,
+};
+
+const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 {This is a weak move
+already.} 4. dxe5 Bxf3 5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5
+(9. Nd5!? (9. a4) cxd5) 9... b5?! $6 10. Nxb5 cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8
+13. Rxd7 Rxd7 14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ ; the queen sacrifice
+Nxb8 17. Rd8# 1-0
+"#;
+
+#[test]
+fn reads_a_game() {
+    let games: Vec<_> = PgnReader::new(OPERA_GAME.as_bytes()).collect();
+    assert_eq!(games.len(), 1);
+    let game = games[0].as_ref().unwrap();
+    assert_eq!(game.tags.len(), 7);
+    assert_eq!(game.tags[0], ("Event".to_string(), "Paris".to_string()));
+    assert_eq!(game.tag("Black"), Some("Duke Karl / Count Isouard"));
+    assert_eq!(game.tag("ECO"), None);
+    assert_eq!(game.result, "1-0");
+    assert_eq!(game.moves.len(), 33);
+
+    //Comments and annotations stay with the move they follow
+    assert_eq!(
+        game.moves[5].comment.as_deref(),
+        Some("This is a weak move already.")
+    );
+    assert_eq!(game.moves[17].nags, vec![6, 6]);
+    assert_eq!(
+        game.moves[30].comment.as_deref(),
+        Some("the queen sacrifice")
+    );
+
+    let positions: Vec<_> = game.positions().unwrap().collect();
+    assert_eq!(positions.len(), 34);
+    assert_eq!(
+        positions[0].to_fen(),
+        game.start_position().unwrap().to_fen()
+    );
+    let last = positions.last().unwrap();
+    assert_eq!(last.outcome(), Outcome::Checkmate(Color::White));
+    assert_eq!(last.fullmove_number(), 17);
+}
+
+#[test]
+fn reads_a_game_from_a_fen_tag() {
+    let pgn = "[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 40\"]\n[SetUp \"1\"]\n\n40... Kd7 41. e4 *\n";
+    let game = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(game.result, "*");
+    assert_eq!(
+        game.positions().unwrap().last().unwrap().to_fen(),
+        "8/3k4/8/8/4P3/8/8/4K3 b - e3 0 41"
+    );
+}
+
+#[test]
+fn bad_games_are_skipped() {
+    let pgn = r#"[Event "first"]
+
+1. e4 e5 1-0
+
+[Event "illegal"]
+
+1. e4 e5 2. Ke3
+2... Nc6 0-1
+
+[Event "bad tag" "twice"]
+[Round "1"]
+
+1. d4 *
+
+[Event "stray paren"]
+
+1. c4 ) c5 *
+
+[Event "last"]
+
+1. Nf3 1/2-1/2
+
+[Event "unterminated"]
+
+1. e4 (1. d4 d5 *
+"#;
+    let games: Vec<_> = PgnReader::new(pgn.as_bytes()).collect();
+    assert_eq!(games.len(), 6);
+    assert_eq!(games[0].as_ref().unwrap().tag("Event"), Some("first"));
+
+    let error = games[1].as_ref().unwrap_err();
+    assert_eq!(error.line, 7);
+    assert_eq!(
+        error.kind,
+        PgnErrorKind::Move("Ke3".to_string(), SanError::Illegal)
+    );
+    assert_eq!(
+        error.to_string(),
+        "line 7: Ke3: no legal move matches the SAN"
+    );
+
+    let error = games[2].as_ref().unwrap_err();
+    assert_eq!((error.line, &error.kind), (10, &PgnErrorKind::MalformedTag));
+
+    let error = games[3].as_ref().unwrap_err();
+    assert_eq!(error.line, 17);
+    assert_eq!(error.kind, PgnErrorKind::UnexpectedToken("')'".to_string()));
+    assert_eq!(games[4].as_ref().unwrap().tag("Event"), Some("last"));
+
+    //Runs off the end looking for the close of the variation
+    let error = games[5].as_ref().unwrap_err();
+    assert_eq!(
+        (error.line, &error.kind),
+        (25, &PgnErrorKind::UnterminatedVariation)
+    );
+}
+
+#[test]
+fn games_without_a_result_end_at_the_next_tags() {
+    let pgn = "[Result \"1/2-1/2\"]\n\n1. e4 e5\n\n[Event \"second\"]\n\n1. d4 *\n";
+    let games: Vec<_> = PgnReader::new(pgn.as_bytes()).map(Result::unwrap).collect();
+    assert_eq!(games.len(), 2);
+    //Taken from the tag when the movetext doesn't give one
+    assert_eq!(games[0].result, "1/2-1/2");
+    assert_eq!(games[0].moves.len(), 2);
+    assert_eq!(games[1].tag("Event"), Some("second"));
+}
+
+#[test]
+fn lexing_errors_report_their_line() {
+    let unterminated = "[Event \"x\"]\n\n1. e4 {never closed\n\n";
+    let errors: Vec<_> = PgnReader::new(unterminated.as_bytes()).collect();
+    assert_eq!(errors.len(), 1);
+    let error = errors[0].as_ref().unwrap_err();
+    assert_eq!(
+        (error.line, &error.kind),
+        (3, &PgnErrorKind::UnterminatedComment)
+    );
+
+    let string = "[Event \"x]\n\n1. e4 *\n[Event \"y\"]\n1. d4 *\n";
+    let games: Vec<_> = PgnReader::new(string.as_bytes()).collect();
+    assert_eq!(
+        games[0].as_ref().unwrap_err().kind,
+        PgnErrorKind::UnterminatedString
+    );
+    assert_eq!(
+        games.last().unwrap().as_ref().unwrap().tag("Event"),
+        Some("y")
+    );
+
+    let character = "1. e4 & e5 *\n";
+    let error = PgnReader::new(character.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(error.kind, PgnErrorKind::UnexpectedCharacter('&'));
+    assert!(PgnReader::new("".as_bytes()).next().is_none());
+}
+
+fn read_one(pgn: &str) -> Game {
+    let mut games = PgnReader::new(pgn.as_bytes());
+    let game = games.next().unwrap().unwrap();
+    assert!(games.next().is_none());
+    game
+}
+
+#[test]
+fn writes_export_format() {
+    let mut game = Game::new();
+    game.set_tag("White", "chessmate");
+    game.set_tag("Black", "Someone \"quoted\"");
+    game.set_tag("TimeControl", "40/300");
+    let board = Board::new();
+    let e4 = board.parse_uci_move("e2e4").unwrap();
+    game.push(e4, Some("+0.35/12".to_string()));
+    let mut board = game.positions().unwrap().last().unwrap();
+    let e5 = board.parse_uci_move("e7e5").unwrap();
+    board.make_move(e5);
+    game.push(e5, None);
+    game.push(board.parse_uci_move("g1f3").unwrap(), None);
+    game.result = "1/2-1/2".to_string();
+    assert_eq!(
+        game.to_pgn().unwrap(),
+        r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "chessmate"]
+[Black "Someone \"quoted\""]
+[Result "1/2-1/2"]
+[TimeControl "40/300"]
+
+1. e4 {+0.35/12} 1... e5 2. Nf3 1/2-1/2
+"#
+    );
+    let read = read_one(&game.to_pgn().unwrap());
+    assert_eq!(read.tag("Black"), Some("Someone \"quoted\""));
+    assert_eq!(read.moves, game.moves);
+    //A closing brace would end the comment early
+    game.moves[1].comment = Some("a } b}".to_string());
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.contains(" e5 {a b} 2. Nf3 "), "{}", pgn);
+    let read = read_one(&pgn);
+    assert_eq!(read.moves[1].comment.as_deref(), Some("a b"));
+}
+
+#[test]
+fn written_games_read_back_the_same() {
+    let game = read_one(OPERA_GAME);
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.lines().all(|line| line.len() <= 80), "{}", pgn);
+    assert!(pgn.contains("9. Bg5 b5 $6 $6 10. Nxb5"), "{}", pgn);
+    assert!(pgn.ends_with(" 17. Rd8# 1-0\n"), "{}", pgn);
+    assert_eq!(read_one(&pgn), game);
+
+    //Starting from a position with black to move
+    let game = read_one("[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 40\"]\n\n40... Kd7 41. e4 *\n");
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN "), "{}", pgn);
+    assert!(pgn.ends_with("\n\n40... Kd7 41. e4 *\n"), "{}", pgn);
+    let read = read_one(&pgn);
+    assert_eq!(read.tag("FEN"), game.tag("FEN"));
+    assert_eq!((read.moves, read.result), (game.moves, game.result));
+}
+
+#[test]
+fn engine_games_round_trip_with_evaluations() {
+    let mut game = Game::new();
+    game.set_tag("Event", "selfplay");
+    let mut board = Board::new();
+    let limits = SearchLimits {
+        depth: Some(2),
+        ..Default::default()
+    };
+    let mut table = TranspositionTable::new(1);
+    for _ in 0..30 {
+        let result = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+        let Some(best_move) = result.best_move else {
+            break;
+        };
+        game.push(best_move, Some(result.pgn_comment()));
+        board.make_move(best_move);
+    }
+    let pgn = game.to_pgn().unwrap();
+    assert!(pgn.lines().all(|line| line.len() <= 80), "{}", pgn);
+    let read = read_one(&pgn);
+    assert_eq!(read.tag("Event"), Some("selfplay"));
+    assert_eq!(read.tag("Date"), Some("????.??.??"));
+    assert_eq!(read.moves, game.moves);
+    let comment = read.moves[0].comment.as_deref().unwrap();
+    assert!(comment.ends_with("/2"), "{}", comment);
+    assert_eq!(
+        read.positions().unwrap().last().unwrap().to_fen(),
+        board.to_fen()
+    );
+}
+
+#[test]
+fn evaluation_comments() {
+    let result = |score| SearchResult {
+        best_move: None,
+        score,
+        depth: 12,
+        nodes: 0,
+        time: Duration::ZERO,
+        pv: Vec::new(),
+    };
+    assert_eq!(result(35).pgn_comment(), "+0.35/12");
+    assert_eq!(result(0).pgn_comment(), "+0.00/12");
+    assert_eq!(result(-250).pgn_comment(), "-2.50/12");
+
+    let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    let limits = SearchLimits {
+        depth: Some(3),
+        ..Default::default()
+    };
+    let mut table = TranspositionTable::new(1);
+    let mate = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+    assert_eq!(mate.pgn_comment(), format!("+M1/{}", mate.depth));
+    let mated = SearchResult {
+        score: -mate.score,
+        ..mate
+    };
+    assert_eq!(mated.pgn_comment(), format!("-M1/{}", mate.depth));
+}
+
+#[test]
+fn bad_fen_tags_are_errors() {
+    let mut game = Game::new();
+    game.set_tag("FEN", "garbage");
+    assert_eq!(game.start_position().err(), Some(FenError::MissingFields));
+    assert!(game.positions().is_err());
+    assert_eq!(game.to_pgn(), Err(FenError::MissingFields));
+}
+
+#[test]
+fn bad_fen_tags_report_their_line() {
+    let pgn = "[Event \"x\"]\n[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n[SetUp \"1\"]\n\n1. e4 *\n";
+    let error = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap_err();
+    assert_eq!(
+        (error.line, &error.kind),
+        (2, &PgnErrorKind::Fen(FenError::PiecePlacement))
+    );
+}
@@ -0,0 +1,107 @@
+use chessmate::{Board, MoveList, SanError, START_FEN};
+
+fn san(fen: &str, uci: &str) -> String {
+    let board = Board::from_fen(fen).unwrap();
+    board.to_san(board.parse_uci_move(uci).unwrap())
+}
+
+fn parse(fen: &str, san: &str) -> Result<String, SanError> {
+    let board = Board::from_fen(fen).unwrap();
+    board.parse_san(san).map(|m| m.to_uci())
+}
+
+#[test]
+fn formats_san() {
+    assert_eq!(san(START_FEN, "g1f3"), "Nf3");
+    assert_eq!(san(START_FEN, "e2e4"), "e4");
+    let scandinavian = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2";
+    assert_eq!(san(scandinavian, "e4d5"), "exd5");
+    let castles = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san(castles, "e1g1"), "O-O");
+    assert_eq!(san(castles, "e1c1"), "O-O-O");
+    assert_eq!(san("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q"), "e8=Q+");
+    assert_eq!(san("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8n"), "e8=N");
+    assert_eq!(
+        san("R2n2k1/5ppp/8/8/8/8/8/3R2K1 w - - 0 1", "a8d8"),
+        "Raxd8#"
+    );
+    let en_passant = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2";
+    assert_eq!(san(en_passant, "e5d6"), "exd6");
+}
+
+#[test]
+fn disambiguates_by_file_then_rank() {
+    assert_eq!(san("R7/8/8/7k/8/8/8/R3K3 w - - 0 1", "a1a4"), "R1a4");
+    let queens = "7K/8/8/8/7Q/k7/8/4Q2Q w - - 0 1";
+    assert_eq!(san(queens, "e1e4"), "Qee4");
+    assert_eq!(san(queens, "h4e4"), "Q4e4");
+    assert_eq!(san(queens, "h1e4"), "Qh1e4");
+    //A pinned knight is no rival
+    assert_eq!(san("4k3/8/8/8/5N2/8/8/K1N1r3 w - - 0 1", "f4d3"), "Nd3");
+}
+
+#[test]
+fn parses_san() {
+    assert_eq!(parse(START_FEN, "Nf3").unwrap(), "g1f3");
+    assert_eq!(parse(START_FEN, "e4").unwrap(), "e2e4");
+    assert_eq!(parse(START_FEN, "Nc3!?").unwrap(), "b1c3");
+    let castles = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1";
+    assert_eq!(parse(castles, "O-O").unwrap(), "e8g8");
+    assert_eq!(parse(castles, "0-0-0").unwrap(), "e8c8");
+    assert_eq!(
+        parse("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e8=Q+").unwrap(),
+        "e7e8q"
+    );
+    assert_eq!(
+        parse("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e8R").unwrap(),
+        "e7e8r"
+    );
+    assert_eq!(
+        parse("7K/8/8/8/7Q/k7/8/4Q2Q w - - 0 1", "Qh1e4").unwrap(),
+        "h1e4"
+    );
+    assert_eq!(
+        parse("R2n2k1/5ppp/8/8/8/8/8/3R2K1 w - - 0 1", "Rdxd8#").unwrap(),
+        "d1d8"
+    );
+}
+
+#[test]
+fn rejects_bad_san() {
+    assert_eq!(parse(START_FEN, "Nf4"), Err(SanError::Illegal));
+    assert_eq!(parse(START_FEN, "O-O"), Err(SanError::Illegal));
+    assert_eq!(parse(START_FEN, "e5"), Err(SanError::Illegal));
+    assert_eq!(parse(START_FEN, "hello"), Err(SanError::Syntax));
+    assert_eq!(parse(START_FEN, ""), Err(SanError::Syntax));
+    assert_eq!(parse(START_FEN, "Ne2=Q"), Err(SanError::Syntax));
+    let rooks = "4k3/8/8/8/8/8/4K3/R6R w - - 0 1";
+    assert_eq!(parse(rooks, "Rd1"), Err(SanError::Ambiguous));
+    assert_eq!(parse(rooks, "Rad1").unwrap(), "a1d1");
+    //A promotion has to name its piece
+    assert_eq!(
+        parse("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e8"),
+        Err(SanError::Illegal)
+    );
+}
+
+#[test]
+fn san_round_trips() {
+    for fen in [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "7K/8/8/8/7Q/k7/8/4Q2Q w - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let mut legal = MoveList::new();
+        board.legal_moves(&mut legal);
+        let mut seen = Vec::new();
+        for board_move in legal.iter() {
+            let san = board.to_san(board_move);
+            assert_eq!(board.parse_san(&san), Ok(board_move), "{} {}", fen, san);
+            assert!(!seen.contains(&san), "{} {}", fen, san);
+            seen.push(san);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::atomic::AtomicBool;
+
+use chessmate::{Board, SearchLimits, SearchResult, TranspositionTable};
+
+fn search(fen: &str, depth: usize) -> SearchResult {
+    let mut board = Board::from_fen(fen).unwrap();
+    let limits = SearchLimits {
+        depth: Some(depth),
+        ..Default::default()
+    };
+    let mut table = TranspositionTable::new(1);
+    let result = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+    assert_eq!(board.to_fen(), fen, "search did not restore the position");
+    result
+}
+
+fn best_move(fen: &str, depth: usize) -> String {
+    search(fen, depth).best_move.unwrap().to_uci()
+}
+
+#[test]
+fn quiescence_sees_recaptures() {
+    //Taking on d5 loses the queen to exd5 just past the horizon
+    assert_ne!(best_move("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", 1), "d1d5");
+    //Undefended, so it can be taken
+    assert_eq!(best_move("4k3/8/8/3p4/8/8/8/3QK3 w - - 0 1", 1), "d1d5");
+}
+
+#[test]
+fn quiescence_finishes_exchanges() {
+    //Nxe5 Nxe5 leaves white a knight for a pawn down however deep the search is cut
+    let result = search("4k3/3n3p/8/4p3/8/5N2/P7/4K3 w - - 0 1", 1);
+    assert_ne!(result.best_move.unwrap().to_uci(), "f3e5");
+    assert!(result.score < 100, "{}", result.score);
+}
+
+#[test]
+fn quiescence_takes_promotions_into_account() {
+    //Nothing stops b1=Q once white has moved
+    let result = search("8/8/8/8/8/2k5/1p4K1/8 w - - 0 1", 1);
+    assert!(result.score < -500, "{}", result.score);
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let result = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+    assert_eq!(result.best_move.unwrap().to_uci(), "a1a8");
+    assert_eq!(result.mate_in(), Some(1));
+}
+
+#[test]
+fn repetition_is_scored_as_a_draw() {
+    //Two queens down, but going back to g1 repeats the position
+    let fen = "k7/8/8/8/1r6/q7/8/7K w - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    for uci in ["h1g1", "a8a7", "g1h1", "a7a8"] {
+        board.make_move(board.parse_uci_move(uci).unwrap());
+    }
+    let limits = SearchLimits {
+        depth: Some(1),
+        ..Default::default()
+    };
+    let mut table = TranspositionTable::new(1);
+    let result = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+    assert_eq!(result.best_move.unwrap().to_uci(), "h1g1");
+    assert_eq!(result.score, 0);
+    assert!(search(fen, 1).score < -500);
+}
+
+#[test]
+fn insufficient_material_is_scored_as_a_draw() {
+    assert_eq!(search("4k3/8/8/8/8/8/8/4KN2 w - - 0 1", 3).score, 0);
+    //Taking the last pawn leaves a bishop that can't win
+    let result = search("4k3/8/8/8/8/8/3p4/2B1K3 w - - 0 1", 2);
+    assert_eq!(result.score, 0);
+}
+
+#[test]
+fn principal_variation_is_legal() {
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+    let result = search(fen, 4);
+    assert_eq!(result.pv.first(), result.best_move.as_ref());
+    assert!(
+        !result.pv.is_empty() && result.pv.len() <= 4,
+        "{:?}",
+        result.pv
+    );
+    let mut board = Board::from_fen(fen).unwrap();
+    for &pv_move in &result.pv {
+        let uci = pv_move.to_uci();
+        assert_eq!(board.parse_uci_move(&uci), Ok(pv_move), "{}", uci);
+        board.make_move(pv_move);
+    }
+}
+
+#[test]
+fn depth_limit_is_respected() {
+    let mut board = Board::new();
+    let limits = SearchLimits {
+        depth: Some(3),
+        ..Default::default()
+    };
+    let mut table = TranspositionTable::new(1);
+    let mut depths = Vec::new();
+    let result = board.search(&limits, &mut table, &AtomicBool::new(false), |info| {
+        depths.push(info.depth)
+    });
+    assert_eq!(result.depth, 3);
+    //One report per completed iteration
+    assert_eq!(depths, [1, 2, 3]);
+}
+
+#[test]
+fn node_limit_is_respected() {
+    let mut board = Board::new();
+    let limits = SearchLimits {
+        nodes: Some(5000),
+        ..Default::default()
+    };
+    let mut table = TranspositionTable::new(1);
+    let result = board.search(&limits, &mut table, &AtomicBool::new(false), |_| ());
+    assert!(result.nodes <= 5000, "{}", result.nodes);
+    assert!(result.depth >= 1 && result.depth < 10, "{}", result.depth);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn stopped_search_still_returns_a_move() {
+    let mut board = Board::new();
+    let mut table = TranspositionTable::new(1);
+    let limits = SearchLimits::default();
+    let result = board.search(&limits, &mut table, &AtomicBool::new(true), |_| ());
+    let best_move = result.best_move.unwrap();
+    assert_eq!(board.parse_uci_move(&best_move.to_uci()), Ok(best_move));
+    assert_eq!(board.to_fen(), chessmate::START_FEN);
+}